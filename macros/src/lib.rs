@@ -0,0 +1,238 @@
+//! Procedural macros for com_ptr.
+//!
+//! Use these macros through the `com_ptr` crate.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{DeriveInput, FnArg, ImplItem, ItemImpl, Path, Token};
+
+struct ClassArgs {
+    interfaces: Punctuated<Path, Token![,]>,
+}
+
+impl Parse for ClassArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(ClassArgs {
+            interfaces: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+struct ImplArgs {
+    interface: Path,
+    base: Option<Path>,
+}
+
+impl Parse for ImplArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let interface = input.parse()?;
+        let base = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(ImplArgs { interface, base })
+    }
+}
+
+/// Returns the path of the vtable, `IFoo` to `IFooVtbl`.
+fn vtbl_path(interface: &Path) -> Path {
+    let mut path = interface.clone();
+    let last = path.segments.last_mut().unwrap();
+    last.ident = format_ident!("{}Vtbl", last.ident);
+    path
+}
+
+/// Makes a type a COM class that implements the specified interfaces.
+///
+/// `#[com_class(IFoo, IBar)]` generates the object layout and the `IUnknown` implementation
+/// of the type, and `into_com_ptr` that creates a new object and returns `ComPtr<IFoo>`.
+/// The methods of each interface are implemented with `#[com_impl]`.
+///
+/// `QueryInterface` for `IUnknown` always returns the first interface.
+#[proc_macro_attribute]
+pub fn com_class(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as ClassArgs);
+    let input = syn::parse_macro_input!(item as DeriveInput);
+    expand_com_class(args, input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand_com_class(args: ClassArgs, input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "a COM class cannot have generic parameters",
+        ));
+    }
+    let interfaces = args.interfaces.iter().collect::<Vec<_>>();
+    let first = match interfaces.first() {
+        Some(first) => first,
+        None => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "a COM class must implement at least one interface",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let len = interfaces.len();
+    let indices = (0..len).collect::<Vec<_>>();
+    Ok(quote! {
+        #input
+
+        unsafe impl ::com_ptr::class::ComClass for #name {
+            type Vtbls = [*const ::com_ptr::sys::c_void; #len];
+
+            const VTBLS: Self::Vtbls = [
+                #(&<#name as ::com_ptr::class::ComVtbl<#interfaces, #indices>>::VTBL as *const _
+                    as *const ::com_ptr::sys::c_void,)*
+            ];
+
+            fn interface_index(iid: &::com_ptr::sys::IID) -> Option<usize> {
+                #(
+                    if <#name as ::com_ptr::class::ComVtbl<#interfaces, #indices>>::has_iid(iid) {
+                        return Some(#indices);
+                    }
+                )*
+                None
+            }
+        }
+
+        impl #name {
+            /// Creates a new COM object and returns the first interface of the class.
+            #[allow(dead_code)]
+            pub fn into_com_ptr(self) -> ::com_ptr::ComPtr<#first> {
+                unsafe { ::com_ptr::class::ComObject::create_at(self, 0) }
+            }
+        }
+    })
+}
+
+/// Implements the methods of an interface for a COM class.
+///
+/// `#[com_impl(IFoo)]` or `#[com_impl(IFoo: IBase)]` is used for an inherent impl of a COM class.
+/// The vtable of `IFoo` must be `IFooVtbl` at the same path, and its first field must be `parent`
+/// that is the vtable of the base interface. When the base interface is omitted, it is `IUnknown`.
+///
+/// Every method in the impl must take `&self` and is placed to the field of the same name in the vtable.
+/// The methods of the base interface are implemented with another `#[com_impl]`.
+///
+/// When the interface implements `Agile`, the type must be `Send` and `Sync` because the interface
+/// is used from any thread.
+#[proc_macro_attribute]
+pub fn com_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as ImplArgs);
+    let input = syn::parse_macro_input!(item as ItemImpl);
+    expand_com_impl(args, input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand_com_impl(args: ImplArgs, input: ItemImpl) -> syn::Result<TokenStream2> {
+    if let Some((_, path, _)) = &input.trait_ {
+        return Err(syn::Error::new(
+            path.span(),
+            "#[com_impl] must be used for an inherent impl",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "a COM class cannot have generic parameters",
+        ));
+    }
+    let self_ty = &input.self_ty;
+    let interface = &args.interface;
+    let vtbl = vtbl_path(interface);
+    let base = match &args.base {
+        Some(base) => quote!(#base),
+        None => quote!(::com_ptr::sys::IUnknown),
+    };
+    let mut shims = Vec::new();
+    let mut fields = Vec::new();
+    for item in &input.items {
+        let method = match item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+        let sig = &method.sig;
+        let ident = &sig.ident;
+        let mut inputs = sig.inputs.iter();
+        match inputs.next() {
+            Some(FnArg::Receiver(r)) if r.reference.is_some() && r.mutability.is_none() => {}
+            _ => {
+                return Err(syn::Error::new(
+                    sig.span(),
+                    "a method of #[com_impl] must take `&self`",
+                ))
+            }
+        }
+        let mut params = Vec::new();
+        let mut tys = Vec::new();
+        for (i, arg) in inputs.enumerate() {
+            match arg {
+                FnArg::Typed(pat) => {
+                    params.push(format_ident!("__arg{}", i));
+                    tys.push(&pat.ty);
+                }
+                FnArg::Receiver(r) => {
+                    return Err(syn::Error::new(r.span(), "unexpected receiver"));
+                }
+            }
+        }
+        let output = &sig.output;
+        let shim = format_ident!("__com_shim_{}", ident);
+        shims.push(quote! {
+            #[allow(non_snake_case)]
+            unsafe extern "system" fn #shim<const __N: usize>(
+                this: *mut #interface,
+                #(#params: #tys,)*
+            ) #output {
+                unsafe {
+                    let obj = ::com_ptr::class::ComObject::<#self_ty>::from_interface::<__N>(
+                        this as *mut ::com_ptr::sys::c_void,
+                    );
+                    <#self_ty>::#ident(&**obj, #(#params,)*)
+                }
+            }
+        });
+        fields.push(quote!(#ident: #shim::<__N>));
+    }
+    Ok(quote! {
+        #input
+
+        const _: fn() = || {
+            #[allow(unused_imports)]
+            use ::com_ptr::class::{AgileClass, NonAgileClass};
+            (&::com_ptr::class::AgileCheck::<#interface, #self_ty>::NEW).check();
+        };
+
+        unsafe impl<const __N: usize> ::com_ptr::class::ComVtbl<#interface, __N> for #self_ty {
+            type Vtbl = #vtbl;
+
+            const VTBL: #vtbl = {
+                #(#shims)*
+                #vtbl {
+                    parent: <#self_ty as ::com_ptr::class::ComVtbl<#base, __N>>::VTBL,
+                    #(#fields,)*
+                }
+            };
+
+            fn has_iid(iid: &::com_ptr::sys::IID) -> bool {
+                ::com_ptr::sys::IsEqualGUID(
+                    iid,
+                    &<#interface as ::com_ptr::sys::Interface>::uuidof(),
+                ) || <#self_ty as ::com_ptr::class::ComVtbl<#base, __N>>::has_iid(iid)
+            }
+        }
+    })
+}
@@ -0,0 +1,107 @@
+#!/usr/bin/env python3
+"""Generates src/table.rs from winerror.h of the Windows SDK.
+
+Usage: scripts/hresult_table.py <path to winerror.h>
+
+The Win32 error codes are converted by HRESULT_FROM_WIN32. When an HRESULT has the same value
+as a Win32 error code, such as E_ACCESSDENIED, the symbol of the HRESULT and the message of the
+Win32 error code are used because FormatMessage returns the message of the Win32 error code.
+The messages are copied from winerror.h. The HRESULTs without a message, such as S_FALSE, and
+the messages with inserts such as `%1` have an empty message because the inserts are not
+formatted.
+"""
+
+import os
+import re
+import sys
+
+MESSAGE_ID = re.compile(r"^//\s*MessageId:\s*(\w+)\s*$")
+MESSAGE_TEXT = re.compile(r"^//\s*MessageText:\s*$")
+DEFINE = re.compile(
+    r"^#define\s+(\w+)\s+(?:_HRESULT_TYPEDEF_\(\s*(0x[0-9A-Fa-f]+)L?\s*\)|(\d+)L)\s*$"
+)
+HRESULT = re.compile(r"^#define\s+(S_OK|S_FALSE)\s+\(\(HRESULT\)(\d+)L\)\s*$")
+
+
+def normalize(message):
+    """Joins the lines and formats the escapes, or returns "" when the message has inserts."""
+    message = " ".join(message.split())
+    if re.search(r"%\d", message):
+        return ""
+    message = message.replace("%%", "\0").replace("%n", " ").replace("%.", ".")
+    message = message.replace("\0", "%")
+    return " ".join(message.split())
+
+
+def parse(path):
+    """Returns the symbols, the values and the messages, and whether they are Win32 codes."""
+    with open(path, encoding="utf-8", errors="replace") as f:
+        lines = f.read().splitlines()
+    symbol, text, in_text = None, [], False
+    for line in lines:
+        line = line.strip()
+        m = MESSAGE_ID.match(line)
+        if m:
+            symbol, text, in_text = m.group(1), [], False
+            continue
+        if MESSAGE_TEXT.match(line):
+            in_text = True
+            continue
+        if in_text and line.startswith("//"):
+            text.append(line[2:])
+            continue
+        in_text = False
+        m = HRESULT.match(line)
+        if m:
+            yield m.group(1), int(m.group(2)), "", False
+            continue
+        m = DEFINE.match(line)
+        if m and m.group(1) == symbol:
+            message = normalize(" ".join(text))
+            if m.group(2):
+                yield symbol, int(m.group(2), 16), message, False
+            elif int(m.group(3)) == 0:
+                # HRESULT_FROM_WIN32(ERROR_SUCCESS) is S_OK.
+                yield symbol, 0, message, True
+            elif int(m.group(3)) <= 0xFFFF:
+                yield symbol, 0x80070000 | int(m.group(3)), message, True
+            symbol, text = None, []
+
+
+def main():
+    if len(sys.argv) != 2:
+        sys.exit(__doc__.strip())
+    table = {}
+    for symbol, value, message, win32 in parse(sys.argv[1]):
+        entry = table.get(value)
+        if entry is None:
+            table[value] = (symbol, message, win32)
+        elif entry[2] and not win32:
+            table[value] = (symbol, entry[1], False)
+        elif win32 and not entry[2]:
+            table[value] = (entry[0], message, False)
+    out = [
+        "//! The symbols and messages of HRESULTs from winerror.h of the Windows SDK.",
+        "//!",
+        "//! This table is sorted by the values and generated by `scripts/hresult_table.py`. The",
+        "//! HRESULTs without a message, such as `S_FALSE`, and the messages with inserts such as `%1`",
+        "//! have an empty message.",
+        "",
+        "pub(crate) static TABLE: &[(u32, &str, &str)] = &[",
+    ]
+    for value in sorted(table):
+        symbol, message, _ = table[value]
+        message = message.replace("\\", "\\\\").replace('"', '\\"')
+        out.append('    (0x{:08X}, "{}", "{}"),'.format(value, symbol, message))
+    out.append("];")
+    dst = os.path.join(os.path.dirname(__file__), "..", "src", "table.rs")
+    with open(dst, encoding="utf-8") as f:
+        src = f.read()
+    # Keeps the code after the table.
+    rest = src[src.index("\n];\n") + len("\n];\n"):]
+    with open(dst, "w", encoding="utf-8", newline="\n") as f:
+        f.write("\n".join(out) + "\n" + rest)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,129 @@
+#!/usr/bin/env python3
+"""Generates src/sys/winapi_interfaces.rs from the RIDL! declarations of winapi.
+
+Usage: scripts/winapi_interfaces.py <path to winapi-0.3.9>
+"""
+
+import os
+import re
+import sys
+
+# The winapi modules and the features that enable them.
+# The modules without features are dependencies of this crate on Windows.
+MODULES = [
+    ("um::unknwnbase", ()),
+    ("um::objidlbase", ()),
+    ("um::oaidl", ()),
+    ("shared::dxgi", ("dxgi",)),
+    ("shared::dxgi1_2", ("dxgi",)),
+    ("shared::dxgi1_3", ("dxgi",)),
+    ("shared::dxgi1_4", ("dxgi",)),
+    ("shared::dxgi1_5", ("dxgi",)),
+    ("shared::dxgi1_6", ("dxgi",)),
+    ("um::d3dcommon", ("d3d11", "d3d12")),
+    ("um::d3d11", ("d3d11",)),
+    ("um::d3d11_1", ("d3d11",)),
+    ("um::d3d11_2", ("d3d11",)),
+    ("um::d3d11sdklayers", ("d3d11",)),
+    ("um::d3d11shader", ("d3d11",)),
+    ("um::d3d12", ("d3d12",)),
+    ("um::d3d12sdklayers", ("d3d12",)),
+    ("um::d3d12shader", ("d3d12",)),
+    ("um::wincodec", ("wincodec",)),
+]
+
+# The features enabled by each feature in Cargo.toml.
+IMPLIES = {
+    "dxgi": {"dxgi"},
+    "d3d11": {"d3d11", "dxgi"},
+    "d3d12": {"d3d12", "dxgi"},
+    "wincodec": {"wincodec"},
+}
+
+# The interfaces that this crate declares by itself.
+SKIP = {"IUnknown"}
+
+RIDL = re.compile(
+    r"RIDL!\{\s*#\[uuid\(([^)]*)\)\]\s*"
+    r"interface\s+(\w+)\s*\(\s*(\w+)\s*\)\s*(?::\s*(\w+)\s*\(\s*\w+\s*\))?\s*\{"
+)
+
+
+def parse(root, module):
+    path = os.path.join(root, "src", *module.split("::")) + ".rs"
+    with open(path, encoding="utf-8") as f:
+        src = f.read()
+    for m in RIDL.finditer(src):
+        iid = [s.strip() for s in m.group(1).split(",") if s.strip()]
+        interface, vtbl, base = m.group(2), m.group(3), m.group(4)
+        # The interfaces without IUnknown are not reference counted.
+        if base is None or interface in SKIP:
+            continue
+        yield interface, vtbl, base, iid
+
+
+def cfg(features):
+    if not features:
+        return None
+    if len(features) == 1:
+        return '#[cfg(feature = "{}")]'.format(features[0])
+    return "#[cfg(any({}))]".format(", ".join('feature = "{}"'.format(f) for f in features))
+
+
+def available(base_features, features):
+    """Returns whether the module of the base is enabled whenever the module is enabled."""
+    if not base_features:
+        return True
+    if not features:
+        return False
+    return all(IMPLIES[f] & set(base_features) for f in features)
+
+
+def main():
+    if len(sys.argv) != 2:
+        sys.exit(__doc__.strip())
+    root = sys.argv[1]
+    modules = [(module, features, list(parse(root, module))) for module, features in MODULES]
+    defined = {"IUnknown": ("um::unknwnbase", ())}
+    for module, features, entries in modules:
+        for interface, _, _, _ in entries:
+            defined[interface] = (module, features)
+    out = [
+        "// Generated by scripts/winapi_interfaces.py from winapi 0.3.9. Do not edit.",
+        "",
+    ]
+    for module, features, entries in modules:
+        if not entries:
+            continue
+        imports = {}
+        for interface, _, base, _ in entries:
+            if base not in defined:
+                sys.exit("{}: the base {} is not in MODULES".format(interface, base))
+            base_module, base_features = defined[base]
+            if not available(base_features, features):
+                sys.exit("{}: the base {} is not always enabled".format(interface, base))
+            if base_module != module:
+                imports.setdefault(base_module, set()).add(base)
+        out.append(cfg(features))
+        out.append("mod {} {{".format(module.split("::")[-1]))
+        for base_module, bases in sorted(imports.items()):
+            if len(bases) == 1:
+                out.append("    use winapi::{}::{};".format(base_module, *bases))
+            else:
+                out.append("    use winapi::{}::{{{}}};".format(base_module, ", ".join(sorted(bases))))
+        out.append("    use winapi::{}::*;".format(module))
+        out.append("")
+        out.append("    winapi_interfaces! {")
+        for interface, vtbl, base, iid in entries:
+            out.append("        {}({}): {} = ({}, {}, {}, [{}]),".format(
+                interface, vtbl, base, iid[0], iid[1], iid[2], ", ".join(iid[3:])))
+        out.append("    }")
+        out.append("}")
+        out.append("")
+    dst = os.path.join(os.path.dirname(__file__), "..", "src", "sys", "winapi_interfaces.rs")
+    with open(dst, "w", encoding="utf-8", newline="\n") as f:
+        f.write("\n".join(line for line in out if line is not None).rstrip("\n") + "\n")
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,133 @@
+//! Thread-safety of interfaces.
+
+use crate::sys::Interface;
+use crate::ComPtr;
+use std::ops::Deref;
+
+/// A marker for interfaces that can be used from any thread.
+///
+/// `ComPtr<T>` is `Send` and `Sync` only when `T` implements this trait.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<com_ptr::ComPtr<com_ptr::sys::IUnknown>>();
+/// ```
+///
+/// A class that implements an `Agile` interface with `#[com_impl]` must be `Send` and `Sync`.
+///
+/// ```compile_fail
+/// use com_ptr::sys::*;
+/// use com_ptr::{com_class, com_impl, com_interface, Agile};
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+///     interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+///         fn Get() -> u32,
+///     }
+/// }
+///
+/// unsafe impl Agile for IValue {}
+///
+/// #[com_class(IValue)]
+/// struct Value(std::cell::Cell<u32>);
+///
+/// #[com_impl(IValue)]
+/// #[allow(non_snake_case)]
+/// impl Value {
+///     fn Get(&self) -> u32 {
+///         self.0.get()
+///     }
+/// }
+/// ```
+///
+/// ## Safety
+/// Every object that implements `T` must be agile or free-threaded, and every Rust type that
+/// implements `T` must be `Send` and `Sync`. `#[com_impl]` checks the latter.
+pub unsafe trait Agile: Interface {}
+
+unsafe impl<T: Agile> Send for ComPtr<T> {}
+unsafe impl<T: Agile> Sync for ComPtr<T> {}
+
+/// A `ComPtr` that is asserted to be used safely from other threads.
+///
+/// This is an escape hatch for interfaces that do not implement `Agile`
+/// but whose objects are known to be agile.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct ThreadSafe<T: Interface>(ComPtr<T>);
+
+impl<T: Interface> ThreadSafe<T> {
+    /// Wraps a `ComPtr`.
+    ///
+    /// ## Safety
+    /// The object that `ptr` points must be able to be used from any thread.
+    #[inline]
+    pub unsafe fn new(ptr: ComPtr<T>) -> ThreadSafe<T> {
+        ThreadSafe(ptr)
+    }
+
+    /// Returns the wrapped `ComPtr`.
+    #[inline]
+    pub fn into_inner(self) -> ComPtr<T> {
+        self.0
+    }
+}
+
+impl<T: Interface> Deref for ThreadSafe<T> {
+    type Target = ComPtr<T>;
+
+    #[inline]
+    fn deref(&self) -> &ComPtr<T> {
+        &self.0
+    }
+}
+
+unsafe impl<T: Interface> Send for ThreadSafe<T> {}
+unsafe impl<T: Interface> Sync for ThreadSafe<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::*;
+    use crate::{com_class, com_impl};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    com_interface! {
+        #[uuid(0x8d2f3c61, 0x4a7b, 0x4c19, 0xb2, 0x5e, 0x0f, 0x93, 0x6a, 0x1d, 0x7c, 0x40)]
+        interface IAgileCounter(IAgileCounterVtbl): IUnknown(IUnknownVtbl) {
+            fn Increment() -> u32,
+        }
+    }
+
+    unsafe impl Agile for IAgileCounter {}
+
+    #[com_class(IAgileCounter)]
+    struct Counter(AtomicU32);
+
+    #[com_impl(IAgileCounter)]
+    #[allow(non_snake_case)]
+    impl Counter {
+        fn Increment(&self) -> u32 {
+            self.0.fetch_add(1, Ordering::Relaxed) + 1
+        }
+    }
+
+    #[test]
+    fn agile_test() {
+        let p = Counter(AtomicU32::new(0)).into_com_ptr();
+        let q = p.clone();
+        std::thread::spawn(move || unsafe { q.Increment() })
+            .join()
+            .unwrap();
+        assert_eq!(unsafe { p.Increment() }, 2);
+    }
+
+    #[test]
+    fn thread_safe_test() {
+        let p = Counter(AtomicU32::new(0)).into_com_ptr();
+        let unknown = unsafe { ThreadSafe::new(p.query_interface::<IUnknown>().unwrap()) };
+        let q = std::thread::spawn(move || unknown.query_interface::<IAgileCounter>().unwrap())
+            .join()
+            .unwrap();
+        assert!(p == q);
+    }
+}
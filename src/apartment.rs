@@ -0,0 +1,280 @@
+//! COM apartments.
+//!
+//! On Windows, [`ComApartment`] calls `CoInitializeEx` and `CoUninitialize`.
+//! On other platforms or with [`Backend::Emulated`], the apartment of each thread is emulated.
+
+use crate::sys::*;
+use crate::{Backend, HResult};
+use std::marker::PhantomData;
+
+/// The kinds of apartments.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ApartmentKind {
+    /// A single-threaded apartment.
+    Sta,
+    /// The multithreaded apartment.
+    Mta,
+    /// The neutral apartment.
+    Neutral,
+}
+
+/// The outcomes of initializing the COM library on the current thread.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ApartmentInit {
+    /// The COM library was initialized on the current thread (`S_OK`).
+    Initialized,
+    /// The COM library was already initialized on the current thread (`S_FALSE`).
+    AlreadyInitialized,
+}
+
+/// The errors of initializing the COM library.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ApartmentError {
+    /// The current thread is already in an apartment of a different kind (`RPC_E_CHANGED_MODE`).
+    ChangedMode,
+    /// Other errors.
+    Failed(HResult),
+}
+
+impl ApartmentError {
+    /// Returns the HRESULT of the error.
+    #[inline]
+    pub fn hresult(&self) -> HResult {
+        match self {
+            ApartmentError::ChangedMode => HResult(RPC_E_CHANGED_MODE),
+            ApartmentError::Failed(hr) => *hr,
+        }
+    }
+}
+
+impl std::fmt::Display for ApartmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ApartmentError::ChangedMode => write!(
+                f,
+                "the current thread is already in an apartment of a different kind"
+            ),
+            ApartmentError::Failed(hr) => write!(f, "{}", hr),
+        }
+    }
+}
+
+impl std::error::Error for ApartmentError {}
+
+impl From<ApartmentError> for HResult {
+    #[inline]
+    fn from(src: ApartmentError) -> HResult {
+        src.hresult()
+    }
+}
+
+/// A guard that keeps the COM library initialized on the current thread.
+///
+/// `CoUninitialize` is called when the guard is dropped.
+///
+/// With the `track-refs` feature, the guard that initialized the COM library prints the
+/// references that were acquired on the thread while the guard was alive and not released, or
+/// passes them to the hook set by `tracker::set_leak_hook`.
+#[derive(Debug)]
+pub struct ComApartment {
+    kind: ApartmentKind,
+    init: ApartmentInit,
+    backend: Backend,
+    #[cfg(feature = "track-refs")]
+    checkpoint: crate::tracker::Checkpoint,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ComApartment {
+    /// Initializes the COM library for use by the current thread as a single-threaded apartment.
+    #[inline]
+    pub fn sta() -> Result<ComApartment, ApartmentError> {
+        ComApartment::new(ApartmentKind::Sta)
+    }
+
+    /// Initializes the COM library for use by the current thread as the multithreaded apartment.
+    #[inline]
+    pub fn mta() -> Result<ComApartment, ApartmentError> {
+        ComApartment::new(ApartmentKind::Mta)
+    }
+
+    fn new(kind: ApartmentKind) -> Result<ComApartment, ApartmentError> {
+        let backend = Backend::current();
+        let res = match backend {
+            #[cfg(windows)]
+            Backend::Com => imp::initialize(kind),
+            _ => emulated::initialize(kind),
+        };
+        let init = match res {
+            S_OK => ApartmentInit::Initialized,
+            S_FALSE => ApartmentInit::AlreadyInitialized,
+            RPC_E_CHANGED_MODE => return Err(ApartmentError::ChangedMode),
+            res => return Err(ApartmentError::Failed(HResult(res))),
+        };
+        Ok(ComApartment {
+            kind,
+            init,
+            backend,
+            #[cfg(feature = "track-refs")]
+            checkpoint: crate::tracker::Checkpoint::new(),
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Returns the kind of the apartment.
+    #[inline]
+    pub fn kind(&self) -> ApartmentKind {
+        self.kind
+    }
+
+    /// Returns whether the COM library was already initialized when the guard was created.
+    #[inline]
+    pub fn init(&self) -> ApartmentInit {
+        self.init
+    }
+
+    /// Returns the kind of the apartment of the current thread.
+    ///
+    /// Returns `None` when the COM library is not initialized on the current thread.
+    #[inline]
+    pub fn current() -> Option<ApartmentKind> {
+        match Backend::current() {
+            #[cfg(windows)]
+            Backend::Com => imp::current(),
+            _ => emulated::current(),
+        }
+    }
+}
+
+impl Drop for ComApartment {
+    fn drop(&mut self) {
+        #[cfg(feature = "track-refs")]
+        {
+            if self.init == ApartmentInit::Initialized {
+                let leaks = self.checkpoint.leaks();
+                if !leaks.is_empty() {
+                    crate::tracker::report_leaks(&leaks);
+                }
+            }
+        }
+        match self.backend {
+            #[cfg(windows)]
+            Backend::Com => imp::uninitialize(),
+            _ => emulated::uninitialize(),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use super::ApartmentKind;
+    use crate::sys::*;
+    use std::ptr::null_mut;
+    use winapi::um::combaseapi::{CoGetApartmentType, CoInitializeEx, CoUninitialize};
+    use winapi::um::objbase::{COINIT_APARTMENTTHREADED, COINIT_MULTITHREADED};
+    use winapi::um::objidlbase::*;
+
+    pub fn initialize(kind: ApartmentKind) -> HRESULT {
+        let coinit = match kind {
+            ApartmentKind::Sta => COINIT_APARTMENTTHREADED,
+            _ => COINIT_MULTITHREADED,
+        };
+        unsafe { CoInitializeEx(null_mut(), coinit) }
+    }
+
+    pub fn uninitialize() {
+        unsafe { CoUninitialize() };
+    }
+
+    pub fn current() -> Option<ApartmentKind> {
+        let mut apt_type = 0;
+        let mut qualifier = 0;
+        let res = unsafe { CoGetApartmentType(&mut apt_type, &mut qualifier) };
+        if res < 0 || qualifier == APTTYPEQUALIFIER_IMPLICIT_MTA {
+            return None;
+        }
+        match apt_type {
+            APTTYPE_STA | APTTYPE_MAINSTA => Some(ApartmentKind::Sta),
+            APTTYPE_MTA => Some(ApartmentKind::Mta),
+            APTTYPE_NA => Some(ApartmentKind::Neutral),
+            _ => None,
+        }
+    }
+}
+
+mod emulated {
+    use super::ApartmentKind;
+    use crate::sys::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static APARTMENT: Cell<(Option<ApartmentKind>, usize)> = const { Cell::new((None, 0)) };
+    }
+
+    pub fn initialize(kind: ApartmentKind) -> HRESULT {
+        APARTMENT.with(|apartment| match apartment.get() {
+            (Some(current), count) if current == kind => {
+                apartment.set((Some(current), count + 1));
+                S_FALSE
+            }
+            (Some(_), _) => RPC_E_CHANGED_MODE,
+            (None, _) => {
+                apartment.set((Some(kind), 1));
+                S_OK
+            }
+        })
+    }
+
+    pub fn uninitialize() {
+        APARTMENT.with(|apartment| match apartment.get() {
+            (Some(_), 1) => apartment.set((None, 0)),
+            (Some(current), count) => apartment.set((Some(current), count - 1)),
+            (None, _) => {}
+        })
+    }
+
+    pub fn current() -> Option<ApartmentKind> {
+        APARTMENT.with(|apartment| apartment.get().0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sta_test() {
+        std::thread::spawn(|| {
+            assert_eq!(ComApartment::current(), None);
+            let sta = ComApartment::sta().unwrap();
+            assert_eq!(sta.kind(), ApartmentKind::Sta);
+            assert_eq!(sta.init(), ApartmentInit::Initialized);
+            assert_eq!(ComApartment::current(), Some(ApartmentKind::Sta));
+            let nested = ComApartment::sta().unwrap();
+            assert_eq!(nested.init(), ApartmentInit::AlreadyInitialized);
+            assert_eq!(ComApartment::mta().err(), Some(ApartmentError::ChangedMode));
+            drop(nested);
+            assert_eq!(ComApartment::current(), Some(ApartmentKind::Sta));
+            drop(sta);
+            assert_eq!(ComApartment::current(), None);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn mta_test() {
+        std::thread::spawn(|| {
+            let mta = ComApartment::mta().unwrap();
+            assert_eq!(mta.kind(), ApartmentKind::Mta);
+            assert_eq!(ComApartment::current(), Some(ApartmentKind::Mta));
+            assert_eq!(ComApartment::sta().err(), Some(ApartmentError::ChangedMode));
+            assert_eq!(
+                HResult::from(ApartmentError::ChangedMode),
+                HResult(RPC_E_CHANGED_MODE)
+            );
+        })
+        .join()
+        .unwrap();
+    }
+}
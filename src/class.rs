@@ -0,0 +1,444 @@
+//! Runtime support for COM classes implemented in Rust.
+//!
+//! The items in this module are used by the code that `#[com_class]` and `#[com_impl]` generate.
+//!
+//! # Examples
+//!
+//! ```
+//! use com_ptr::sys::*;
+//! use com_ptr::{com_class, com_impl, ComPtr};
+//! # #[repr(C)]
+//! # pub struct IValue { lpVtbl: *const IValueVtbl }
+//! # #[repr(C)]
+//! # #[allow(non_snake_case)]
+//! # pub struct IValueVtbl {
+//! #     parent: IUnknownVtbl,
+//! #     GetValue: unsafe extern "system" fn(This: *mut IValue, value: *mut u32) -> HRESULT,
+//! # }
+//! # unsafe impl Interface for IValue {
+//! #     fn uuidof() -> GUID {
+//! #         GUID { Data1: 1, Data2: 2, Data3: 3, Data4: [4, 5, 6, 7, 8, 9, 10, 11] }
+//! #     }
+//! # }
+//!
+//! #[com_class(IValue)]
+//! struct Value(u32);
+//!
+//! #[com_impl(IValue)]
+//! #[allow(non_snake_case)]
+//! impl Value {
+//!     unsafe fn GetValue(&self, value: *mut u32) -> HRESULT {
+//!         *value = self.0;
+//!         S_OK
+//!     }
+//! }
+//!
+//! let p: ComPtr<IValue> = Value(42).into_com_ptr();
+//! let unknown = p.query_interface::<IUnknown>().unwrap();
+//! ```
+
+use crate::sys::*;
+use crate::{ComPtr, HResult};
+use std::ops::Deref;
+use std::ptr::{addr_of_mut, null_mut};
+use std::sync::atomic::{self, AtomicU32, Ordering};
+
+/// A type that can be a COM object.
+///
+/// This trait is implemented by `#[com_class]`.
+///
+/// ## Safety
+/// `Vtbls` must be an array of pointers, and the pointer at an index must point the vtable of
+/// the interface that `interface_index` returns the index for.
+pub unsafe trait ComClass: Sized {
+    /// The type of the array of vtable pointers.
+    type Vtbls: Copy;
+
+    /// The vtable pointers of the interfaces that the class implements.
+    const VTBLS: Self::Vtbls;
+
+    /// Returns the index of the vtable pointer for `iid`.
+    fn interface_index(iid: &IID) -> Option<usize>;
+}
+
+/// A vtable of interface `I` for a class when the vtable pointer is placed at index `N`.
+///
+/// This trait is implemented by `#[com_impl]`.
+///
+/// ## Safety
+/// `VTBL` must be the vtable of `I`.
+pub unsafe trait ComVtbl<I, const N: usize> {
+    /// The type of the vtable.
+    type Vtbl;
+
+    /// The vtable.
+    const VTBL: Self::Vtbl;
+
+    /// Returns `true` when `iid` is the IID of `I` or one of its base interfaces.
+    fn has_iid(iid: &IID) -> bool;
+}
+
+unsafe impl<C: ComClass, const N: usize> ComVtbl<IUnknown, N> for C {
+    type Vtbl = IUnknownVtbl;
+
+    const VTBL: IUnknownVtbl = IUnknownVtbl {
+        QueryInterface: query_interface::<C, N>,
+        AddRef: add_ref::<C, N>,
+        Release: release::<C, N>,
+    };
+
+    #[inline]
+    fn has_iid(iid: &IID) -> bool {
+        IsEqualGUID(iid, &IUnknown::uuidof())
+    }
+}
+
+/// Checks that a class implementing an [`Agile`](crate::Agile) interface `I` is `Send` and `Sync`.
+///
+/// `#[com_impl]` calls `(&AgileCheck::<I, C>::NEW).check()`. When `I` implements `Agile`,
+/// [`AgileClass::check`] is selected and requires `C: Send + Sync`. Otherwise
+/// [`NonAgileClass::check`] is selected through the auto-reference and requires nothing.
+#[doc(hidden)]
+pub struct AgileCheck<I, C>(std::marker::PhantomData<(*const I, *const C)>);
+
+impl<I, C> AgileCheck<I, C> {
+    pub const NEW: Self = AgileCheck(std::marker::PhantomData);
+}
+
+#[doc(hidden)]
+pub trait AgileClass<C> {
+    #[inline]
+    fn check(&self)
+    where
+        C: Send + Sync,
+    {
+    }
+}
+
+impl<I: crate::Agile, C> AgileClass<C> for AgileCheck<I, C> {}
+
+#[doc(hidden)]
+pub trait NonAgileClass {
+    #[inline]
+    fn check(&self) {}
+}
+
+impl<I, C> NonAgileClass for &AgileCheck<I, C> {}
+
+/// A COM object that holds a value of a class.
+#[repr(C)]
+pub struct ComObject<C: ComClass> {
+    vtbls: C::Vtbls,
+    count: AtomicU32,
+    value: C,
+}
+
+impl<C: ComClass> ComObject<C> {
+    /// Creates a new COM object and returns a `ComPtr<I>` when `C` implements `I`.
+    pub fn create<I: Interface>(value: C) -> Result<ComPtr<I>, HResult> {
+        let index = C::interface_index(&I::uuidof()).ok_or(HResult(E_NOINTERFACE))?;
+        unsafe { Ok(Self::create_at(value, index)) }
+    }
+
+    /// Creates a new COM object and returns the interface at `index`.
+    ///
+    /// ## Safety
+    /// The vtable pointer at `index` must be the vtable of `I`.
+    pub unsafe fn create_at<I: Interface>(value: C, index: usize) -> ComPtr<I> {
+        let obj = Box::into_raw(Box::new(ComObject {
+            vtbls: C::VTBLS,
+            count: AtomicU32::new(1),
+            value,
+        }));
+        ComPtr::from_raw(Self::interface_at(obj, index) as *mut I)
+    }
+
+    /// Returns the object from an interface pointer at index `N`.
+    ///
+    /// The pointer has the provenance of the whole object because the interface pointers are
+    /// derived from the pointer of the allocation.
+    ///
+    /// ## Safety
+    /// `this` must be the interface pointer at index `N` of a `ComObject<C>`.
+    #[inline]
+    pub unsafe fn from_interface<const N: usize>(this: *mut c_void) -> *mut ComObject<C> {
+        (this as *mut *const c_void).sub(N) as *mut ComObject<C>
+    }
+
+    #[inline]
+    unsafe fn interface_at(obj: *mut ComObject<C>, index: usize) -> *mut c_void {
+        (addr_of_mut!((*obj).vtbls) as *mut *const c_void).add(index) as *mut c_void
+    }
+}
+
+impl<C: ComClass> Deref for ComObject<C> {
+    type Target = C;
+
+    #[inline]
+    fn deref(&self) -> &C {
+        &self.value
+    }
+}
+
+unsafe extern "system" fn query_interface<C: ComClass, const N: usize>(
+    this: *mut IUnknown,
+    riid: REFIID,
+    ppv: *mut *mut c_void,
+) -> HRESULT {
+    if ppv.is_null() {
+        return E_POINTER;
+    }
+    let obj = ComObject::<C>::from_interface::<N>(this as *mut c_void);
+    let iid = &*riid;
+    let index = if IsEqualGUID(iid, &IUnknown::uuidof()) {
+        Some(0)
+    } else {
+        C::interface_index(iid)
+    };
+    match index {
+        Some(index) => {
+            (*obj).count.fetch_add(1, Ordering::Relaxed);
+            *ppv = ComObject::interface_at(obj, index);
+            S_OK
+        }
+        None => {
+            *ppv = null_mut();
+            E_NOINTERFACE
+        }
+    }
+}
+
+unsafe extern "system" fn add_ref<C: ComClass, const N: usize>(this: *mut IUnknown) -> ULONG {
+    let obj = ComObject::<C>::from_interface::<N>(this as *mut c_void);
+    (*obj).count.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+unsafe extern "system" fn release<C: ComClass, const N: usize>(this: *mut IUnknown) -> ULONG {
+    let obj = ComObject::<C>::from_interface::<N>(this as *mut c_void);
+    let count = (*obj).count.fetch_sub(1, Ordering::Release) - 1;
+    if count == 0 {
+        atomic::fence(Ordering::Acquire);
+        drop(Box::from_raw(obj));
+    }
+    count
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{com_class, com_impl};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    #[repr(C)]
+    pub struct IValue {
+        lpVtbl: *const IValueVtbl,
+    }
+
+    #[repr(C)]
+    pub struct IValueVtbl {
+        parent: IUnknownVtbl,
+        GetValue: unsafe extern "system" fn(This: *mut IValue, value: *mut u32) -> HRESULT,
+    }
+
+    unsafe impl Interface for IValue {
+        fn uuidof() -> GUID {
+            GUID {
+                Data1: 0x6f1a_2b3c,
+                Data2: 0x0d4e,
+                Data3: 0x4f50,
+                Data4: [0x81, 0x92, 0xa3, 0xb4, 0xc5, 0xd6, 0xe7, 0xf8],
+            }
+        }
+    }
+
+    impl IValue {
+        unsafe fn GetValue(&self, value: *mut u32) -> HRESULT {
+            ((*self.lpVtbl).GetValue)(self as *const _ as *mut _, value)
+        }
+    }
+
+    #[repr(C)]
+    pub struct IValue2 {
+        lpVtbl: *const IValue2Vtbl,
+    }
+
+    #[repr(C)]
+    pub struct IValue2Vtbl {
+        parent: IValueVtbl,
+        SetValue: unsafe extern "system" fn(This: *mut IValue2, value: u32) -> HRESULT,
+    }
+
+    unsafe impl Interface for IValue2 {
+        fn uuidof() -> GUID {
+            GUID {
+                Data1: 0x6f1a_2b3d,
+                Data2: 0x0d4e,
+                Data3: 0x4f50,
+                Data4: [0x81, 0x92, 0xa3, 0xb4, 0xc5, 0xd6, 0xe7, 0xf8],
+            }
+        }
+    }
+
+    impl IValue2 {
+        unsafe fn SetValue(&self, value: u32) -> HRESULT {
+            ((*self.lpVtbl).SetValue)(self as *const _ as *mut _, value)
+        }
+    }
+
+    #[repr(C)]
+    pub struct IName {
+        lpVtbl: *const INameVtbl,
+    }
+
+    #[repr(C)]
+    pub struct INameVtbl {
+        parent: IUnknownVtbl,
+        GetLength: unsafe extern "system" fn(This: *mut IName) -> u32,
+    }
+
+    unsafe impl Interface for IName {
+        fn uuidof() -> GUID {
+            GUID {
+                Data1: 0x6f1a_2b3e,
+                Data2: 0x0d4e,
+                Data3: 0x4f50,
+                Data4: [0x81, 0x92, 0xa3, 0xb4, 0xc5, 0xd6, 0xe7, 0xf8],
+            }
+        }
+    }
+
+    impl IName {
+        unsafe fn GetLength(&self) -> u32 {
+            ((*self.lpVtbl).GetLength)(self as *const _ as *mut _)
+        }
+    }
+
+    #[com_class(IValue2, IName)]
+    struct Object {
+        value: AtomicU32,
+        name: &'static str,
+        dropped: Arc<AtomicBool>,
+    }
+
+    #[com_impl(IValue)]
+    impl Object {
+        unsafe fn GetValue(&self, value: *mut u32) -> HRESULT {
+            if value.is_null() {
+                return E_POINTER;
+            }
+            *value = self.value.load(Ordering::Relaxed);
+            S_OK
+        }
+    }
+
+    #[com_impl(IValue2: IValue)]
+    impl Object {
+        fn SetValue(&self, value: u32) -> HRESULT {
+            self.value.store(value, Ordering::Relaxed);
+            S_OK
+        }
+    }
+
+    #[com_impl(IName)]
+    impl Object {
+        fn GetLength(&self) -> u32 {
+            self.name.len() as u32
+        }
+    }
+
+    impl Drop for Object {
+        fn drop(&mut self) {
+            self.dropped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn new_object(dropped: &Arc<AtomicBool>) -> ComPtr<IValue2> {
+        Object {
+            value: AtomicU32::new(1),
+            name: "object",
+            dropped: dropped.clone(),
+        }
+        .into_com_ptr()
+    }
+
+    #[test]
+    fn methods_test() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let p = new_object(&dropped);
+        unsafe {
+            assert_eq!(p.SetValue(7), S_OK);
+            let value2 = p.query_interface::<IValue>().unwrap();
+            let mut value = 0;
+            assert_eq!(value2.GetValue(&mut value), S_OK);
+            assert_eq!(value, 7);
+            let name = p.query_interface::<IName>().unwrap();
+            assert_eq!(name.GetLength(), 6);
+        }
+    }
+
+    #[test]
+    fn identity_test() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let p = new_object(&dropped);
+        let name = p.query_interface::<IName>().unwrap();
+        let a = p.query_interface::<IUnknown>().unwrap();
+        let b = name.query_interface::<IUnknown>().unwrap();
+        assert!(a == b);
+        assert_eq!(a.as_ptr() as usize, p.as_ptr() as usize);
+        assert_ne!(name.as_ptr() as usize, p.as_ptr() as usize);
+        let value = name.query_interface::<IValue2>().unwrap();
+        assert!(value == p);
+    }
+
+    #[test]
+    fn ref_count_test() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let p = new_object(&dropped);
+        let name = p.query_interface::<IName>().unwrap();
+        name.add_ref();
+        let q = p.clone();
+        drop(p);
+        unsafe { name.release() };
+        assert!(!dropped.load(Ordering::Relaxed));
+        drop(name);
+        assert!(!dropped.load(Ordering::Relaxed));
+        drop(q);
+        assert!(dropped.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn create_test() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let obj = || Object {
+            value: AtomicU32::new(3),
+            name: "",
+            dropped: dropped.clone(),
+        };
+        let p = ComObject::create::<IName>(obj()).unwrap();
+        unsafe { assert_eq!(p.GetLength(), 0) };
+        assert_eq!(
+            ComObject::create::<IValueUnsupported>(obj()).err(),
+            Some(HResult(E_NOINTERFACE))
+        );
+        drop(p);
+        assert!(dropped.load(Ordering::Relaxed));
+    }
+
+    #[repr(C)]
+    struct IValueUnsupported {
+        lpVtbl: *const IUnknownVtbl,
+    }
+
+    unsafe impl Interface for IValueUnsupported {
+        fn uuidof() -> GUID {
+            GUID {
+                Data1: 0x6f1a_2b3f,
+                Data2: 0x0d4e,
+                Data3: 0x4f50,
+                Data4: [0x81, 0x92, 0xa3, 0xb4, 0xc5, 0xd6, 0xe7, 0xf8],
+            }
+        }
+    }
+}
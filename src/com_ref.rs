@@ -0,0 +1,192 @@
+//! Borrowed interfaces.
+
+use crate::sys::*;
+use crate::{Agile, ComPtr, HResult, Inherits};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+/// A non-owning reference to a COM interface.
+///
+/// `ComRef<'a, T>` is `Copy` and does not call `AddRef` and `Release`.
+/// The reference is valid while `'a` because the owner keeps the reference count.
+///
+/// `ComRef<'a, T>` is `Send` and `Sync` only when `T` implements [`Agile`].
+pub struct ComRef<'a, T: Interface> {
+    p: NonNull<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: Interface> ComRef<'a, T> {
+    /// Creates a `ComRef` from a non-null raw pointer.
+    ///
+    /// # Panics
+    /// Panics if `ptr` is null. Use [`ComRef::from_raw_opt`] for the pointers that can be null
+    /// such as the parameters of callbacks.
+    ///
+    /// ## Safety
+    /// 'ptr' must be non-null and valid while `'a`.
+    #[inline]
+    pub unsafe fn from_raw(ptr: *mut T) -> ComRef<'a, T> {
+        ComRef::from_raw_opt(ptr).expect("ComRef should not be null.")
+    }
+
+    /// Creates a `ComRef` from a raw pointer such as a parameter of a callback.
+    ///
+    /// Returns `None` when `ptr` is null.
+    ///
+    /// ## Safety
+    /// 'ptr' must be null or valid while `'a`.
+    #[inline]
+    pub unsafe fn from_raw_opt(ptr: *mut T) -> Option<ComRef<'a, T>> {
+        NonNull::new(ptr).map(|p| ComRef {
+            p,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns a pointer
+    #[inline]
+    pub fn as_ptr(&self) -> *mut T {
+        self.p.as_ptr()
+    }
+
+    /// Returns a `ComPtr<U>` when interface `T` support interface `U`.
+    pub fn query_interface<U: Interface>(&self) -> Result<ComPtr<U>, HResult> {
+        unsafe {
+            ComPtr::from_iid_out(|iid, p| {
+                (*(self.as_ptr() as *mut IUnknown)).QueryInterface(iid, p)
+            })
+        }
+    }
+
+    /// Returns a `ComPtr<T>` that owns a new reference by `AddRef`.
+    #[inline]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_owned(self) -> ComPtr<T> {
+        unsafe {
+            (*(self.as_ptr() as *mut IUnknown)).AddRef();
+            ComPtr::from_raw(self.as_ptr())
+        }
+    }
+
+    /// Converts into a `ComRef` of the base interface without `QueryInterface`.
+    #[inline]
+    pub fn upcast<U: Interface>(self) -> ComRef<'a, U>
+    where
+        T: Inherits<U>,
+    {
+        ComRef {
+            p: self.p.cast(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Interface> ComPtr<T> {
+    /// Returns a `ComRef` that borrows the interface.
+    #[inline]
+    pub fn as_ref_ptr(&self) -> ComRef<'_, T> {
+        ComRef {
+            p: self.p,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Interface> From<&'a ComPtr<T>> for ComRef<'a, T> {
+    #[inline]
+    fn from(src: &'a ComPtr<T>) -> ComRef<'a, T> {
+        src.as_ref_ptr()
+    }
+}
+
+impl<'a, T: Interface> Clone for ComRef<'a, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: Interface> Copy for ComRef<'a, T> {}
+
+impl<'a, T: Interface> Deref for ComRef<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.as_ref()
+    }
+}
+
+impl<'a, T: Interface> std::convert::AsRef<T> for ComRef<'a, T> {
+    #[inline]
+    fn as_ref(&self) -> &T {
+        unsafe { self.p.as_ref() }
+    }
+}
+
+impl<'a, T: Interface> PartialEq for ComRef<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ptr() == other.as_ptr()
+    }
+}
+
+impl<'a, T: Interface> Eq for ComRef<'a, T> {}
+
+impl<'a, T: Interface> PartialEq<ComPtr<T>> for ComRef<'a, T> {
+    fn eq(&self, other: &ComPtr<T>) -> bool {
+        self.as_ptr() == other.as_ptr()
+    }
+}
+
+impl<'a, T: Interface> std::fmt::Debug for ComRef<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_ptr())
+    }
+}
+
+unsafe impl<'a, T: Agile> Send for ComRef<'a, T> {}
+unsafe impl<'a, T: Agile> Sync for ComRef<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{count, IValue, Value};
+
+    unsafe extern "system" fn callback(p: *mut IValue) -> u32 {
+        match ComRef::from_raw_opt(p) {
+            Some(r) => r.Get(),
+            None => 0,
+        }
+    }
+
+    #[test]
+    fn borrow_test() {
+        let p = Value::new(3).into_com_ptr();
+        let r = p.as_ref_ptr();
+        let r2 = r;
+        assert_eq!(count(r), 1);
+        assert_eq!(unsafe { r2.Get() }, 3);
+        assert!(r == r2);
+        assert!(r == p);
+        assert_eq!(unsafe { callback(p.as_ptr()) }, 3);
+        assert_eq!(unsafe { callback(std::ptr::null_mut()) }, 0);
+        assert_eq!(count(r), 1);
+    }
+
+    #[test]
+    fn owned_test() {
+        let p = Value::new(3).into_com_ptr();
+        let r = ComRef::from(&p);
+        let q = r.to_owned();
+        assert!(p == q);
+        assert_eq!(count(r), 2);
+        let unknown = r.query_interface::<IUnknown>().unwrap();
+        assert_eq!(count(r), 3);
+        assert!(r.upcast::<IUnknown>() == unknown);
+        drop(q);
+        drop(unknown);
+        assert_eq!(count(r), 1);
+    }
+}
@@ -0,0 +1,368 @@
+//! HRESULT errors with the context of the failed calls.
+
+use crate::sys::HRESULT;
+use crate::{hresult, ComError, HResult};
+use std::borrow::Cow;
+use std::error::Error;
+use std::panic::Location;
+
+/// A HResult with a message, the location of the call site and an optional source error.
+///
+/// The underlying `HResult` is available by [`ContextError::hresult`] and [`ContextError::downcast_ref`].
+#[derive(Debug)]
+pub struct ContextError {
+    hr: HResult,
+    message: Cow<'static, str>,
+    location: &'static Location<'static>,
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+impl ContextError {
+    /// Creates a `ContextError` at the location of the caller.
+    #[track_caller]
+    #[inline]
+    pub fn new(hr: HResult, message: impl Into<Cow<'static, str>>) -> ContextError {
+        ContextError {
+            hr,
+            message: message.into(),
+            location: Location::caller(),
+            source: None,
+        }
+    }
+
+    /// Sets the source error.
+    #[inline]
+    pub fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> ContextError {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Returns the underlying HResult.
+    #[inline]
+    pub fn hresult(&self) -> HResult {
+        self.hr
+    }
+
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[inline]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Adds a context to the error. `self` becomes the source error.
+    #[track_caller]
+    #[inline]
+    pub fn context(self, message: impl Into<Cow<'static, str>>) -> ContextError {
+        let hr = self.hr;
+        ContextError::new(hr, message).with_source(self)
+    }
+
+    /// Returns the first error of type `E` in the chain of the errors.
+    ///
+    /// `downcast_ref::<HResult>()` always returns the underlying HResult.
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        let mut current: Option<&(dyn Error + 'static)> = Some(self);
+        while let Some(e) = current {
+            if let Some(e) = e.downcast_ref::<E>() {
+                return Some(e);
+            }
+            current = e.source();
+        }
+        (&self.hr as &(dyn Error + 'static)).downcast_ref::<E>()
+    }
+}
+
+/// Displays as `message at src/main.rs:10:5 (0x80004005)`.
+///
+/// The alternate form `{:#}` appends the chain of the source errors separated by `: ` such as
+/// `outer at src/main.rs:10:5 (0x80004005): inner at src/main.rs:4:5 (0x80004005): Unspecified error`.
+/// The source error or the HResult is available by `Error::source`, so that reporters such as
+/// `anyhow` print each error of the chain once with the plain form.
+impl std::fmt::Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {} (0x{:08X})",
+            self.message, self.location, self.hr.0
+        )?;
+        if f.alternate() {
+            let mut source = self.source();
+            while let Some(e) = source {
+                write!(f, ": {}", e)?;
+                source = e.source();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for ContextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.source {
+            Some(source) => Some(source.as_ref()),
+            None => Some(&self.hr),
+        }
+    }
+}
+
+impl From<ContextError> for HResult {
+    #[inline]
+    fn from(src: ContextError) -> HResult {
+        src.hr
+    }
+}
+
+impl HResult {
+    /// Returns a `ContextError` with `message` at the location of the caller.
+    #[track_caller]
+    #[inline]
+    pub fn context(self, message: impl Into<Cow<'static, str>>) -> ContextError {
+        ContextError::new(self, message)
+    }
+}
+
+mod private {
+    use super::*;
+
+    pub trait Sealed {
+        fn into_context(
+            self,
+            message: Cow<'static, str>,
+            location: &'static Location<'static>,
+        ) -> ContextError;
+    }
+
+    impl Sealed for HResult {
+        fn into_context(
+            self,
+            message: Cow<'static, str>,
+            location: &'static Location<'static>,
+        ) -> ContextError {
+            ContextError {
+                hr: self,
+                message,
+                location,
+                source: None,
+            }
+        }
+    }
+
+    impl Sealed for ComError {
+        fn into_context(
+            self,
+            message: Cow<'static, str>,
+            location: &'static Location<'static>,
+        ) -> ContextError {
+            ContextError {
+                hr: self.hresult(),
+                message,
+                location,
+                source: Some(Box::new(self)),
+            }
+        }
+    }
+
+    impl Sealed for ContextError {
+        fn into_context(
+            self,
+            message: Cow<'static, str>,
+            location: &'static Location<'static>,
+        ) -> ContextError {
+            ContextError {
+                hr: self.hr,
+                message,
+                location,
+                source: Some(Box::new(self)),
+            }
+        }
+    }
+}
+
+/// Adds contexts to `Result<T, HResult>`, `Result<T, ComError>` and `Result<T, ContextError>`.
+///
+/// ```
+/// use com_ptr::{HResult, ResultExt};
+///
+/// fn get_frame() -> Result<u32, HResult> {
+///     Err(HResult::E_FAIL)
+/// }
+///
+/// let e = get_frame().context("IWICBitmapDecoder::GetFrame").unwrap_err();
+/// assert_eq!(e.hresult(), HResult::E_FAIL);
+/// ```
+pub trait ResultExt<T>: Sized {
+    /// Returns a `ContextError` with `message` when `self` is an error.
+    #[track_caller]
+    fn context(self, message: impl Into<Cow<'static, str>>) -> Result<T, ContextError>;
+
+    /// Returns a `ContextError` with the message from `f` when `self` is an error.
+    #[track_caller]
+    fn with_context<F, M>(self, f: F) -> Result<T, ContextError>
+    where
+        F: FnOnce() -> M,
+        M: Into<Cow<'static, str>>;
+}
+
+impl<T, E: private::Sealed> ResultExt<T> for Result<T, E> {
+    #[track_caller]
+    #[inline]
+    fn context(self, message: impl Into<Cow<'static, str>>) -> Result<T, ContextError> {
+        match self {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into_context(message.into(), Location::caller())),
+        }
+    }
+
+    #[track_caller]
+    #[inline]
+    fn with_context<F, M>(self, f: F) -> Result<T, ContextError>
+    where
+        F: FnOnce() -> M,
+        M: Into<Cow<'static, str>>,
+    {
+        match self {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into_context(f().into(), Location::caller())),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+#[inline]
+pub fn hresult_context<T>(
+    obj: T,
+    res: HRESULT,
+    message: impl Into<Cow<'static, str>>,
+) -> Result<T, ContextError> {
+    hresult(obj, res).context(message)
+}
+
+/// The same as [`hresult`](fn@crate::hresult) but returns a [`ContextError`] with the location.
+///
+/// The message is the stringified `res` when it is omitted.
+///
+/// ```
+/// use com_ptr::{hresult, HResult};
+///
+/// let res = HResult::E_FAIL.code();
+/// let e = hresult!(1, res, "IWICBitmapDecoder::GetFrame").unwrap_err();
+/// assert_eq!(e.message(), "IWICBitmapDecoder::GetFrame");
+/// let e = hresult!(1, res).unwrap_err();
+/// assert_eq!(e.message(), "res");
+/// ```
+#[macro_export]
+macro_rules! hresult {
+    ($obj:expr, $res:expr $(,)?) => {
+        $crate::hresult_context($obj, $res, stringify!($res))
+    };
+    ($obj:expr, $res:expr, $message:expr $(,)?) => {
+        $crate::hresult_context($obj, $res, $message)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_test() {
+        let line = line!() + 1;
+        let e = HResult::E_FAIL.context("IFoo::Bar");
+        assert_eq!(e.hresult(), HResult::E_FAIL);
+        assert_eq!(e.message(), "IFoo::Bar");
+        assert_eq!(e.location().file(), file!());
+        assert_eq!(e.location().line(), line);
+        assert_eq!(
+            e.to_string(),
+            format!("IFoo::Bar at {} (0x80004005)", e.location())
+        );
+        assert_eq!(
+            format!("{:#}", e),
+            format!(
+                "IFoo::Bar at {} (0x80004005): {}",
+                e.location(),
+                HResult::E_FAIL
+            )
+        );
+        assert_eq!(e.source().unwrap().downcast_ref(), Some(&HResult::E_FAIL));
+    }
+
+    #[test]
+    fn chain_test() {
+        fn inner() -> Result<(), ContextError> {
+            Err(HResult::E_ACCESSDENIED).context("inner")
+        }
+        fn outer() -> Result<(), ContextError> {
+            inner().with_context(|| format!("outer {}", 1))
+        }
+        let e = outer().unwrap_err();
+        let inner = e.source().unwrap().downcast_ref::<ContextError>().unwrap();
+        assert_eq!(inner.message(), "inner");
+        assert_eq!(
+            e.to_string(),
+            format!("outer 1 at {} (0x80070005)", e.location())
+        );
+        assert_eq!(
+            inner.to_string(),
+            format!("inner at {} (0x80070005)", inner.location())
+        );
+        assert_eq!(
+            format!("{:#}", e),
+            format!(
+                "outer 1 at {} (0x80070005): inner at {} (0x80070005): {}",
+                e.location(),
+                inner.location(),
+                HResult::E_ACCESSDENIED
+            )
+        );
+        assert_eq!(e.downcast_ref::<HResult>(), Some(&HResult::E_ACCESSDENIED));
+        assert_eq!(HResult::from(e), HResult::E_ACCESSDENIED);
+    }
+
+    #[test]
+    fn com_error_test() {
+        let e = Err::<(), _>(ComError::new(HResult::E_FAIL).with_description("failed"))
+            .context("IFoo::Bar")
+            .unwrap_err();
+        assert_eq!(e.hresult(), HResult::E_FAIL);
+        assert_eq!(
+            e.downcast_ref::<ComError>().unwrap().description(),
+            Some("failed")
+        );
+        assert_eq!(e.downcast_ref::<HResult>(), Some(&HResult::E_FAIL));
+    }
+
+    #[test]
+    fn macro_test() {
+        let res = HResult::E_POINTER.code();
+        assert_eq!(hresult!(1, crate::sys::S_OK).unwrap(), 1);
+        let line = line!() + 1;
+        let e = hresult!((), res, "IFoo::Bar").unwrap_err();
+        assert_eq!(e.message(), "IFoo::Bar");
+        assert_eq!(e.location().line(), line);
+        assert_eq!(e.hresult(), HResult::E_POINTER);
+        let e = hresult!((), res).unwrap_err();
+        assert_eq!(e.message(), "res");
+    }
+
+    #[test]
+    fn anyhow_test() {
+        fn f() -> anyhow::Result<()> {
+            Err(HResult::E_FAIL).context("IFoo::Bar")?;
+            Ok(())
+        }
+        let e = f().unwrap_err();
+        assert_eq!(
+            e.downcast_ref::<ContextError>().unwrap().hresult(),
+            HResult::E_FAIL
+        );
+        let chain = e.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[1], HResult::E_FAIL.to_string());
+    }
+}
@@ -0,0 +1,400 @@
+//! Errors with the error information of COM.
+//!
+//! On Windows, [`ComError`] reads and writes the error information with `GetErrorInfo` and
+//! `SetErrorInfo`. On other platforms, the error information of each thread is emulated.
+
+use crate::sys::*;
+use crate::{ComPtr, Guid, HResult};
+
+/// A HResult with the error information such as `IErrorInfo`.
+#[derive(Clone)]
+pub struct ComError {
+    hr: HResult,
+    description: Option<String>,
+    source: Option<String>,
+    guid: Option<GUID>,
+    help_file: Option<String>,
+    help_context: u32,
+}
+
+impl ComError {
+    /// Creates a `ComError` without the error information.
+    #[inline]
+    pub fn new(hr: HResult) -> ComError {
+        ComError {
+            hr,
+            description: None,
+            source: None,
+            guid: None,
+            help_file: None,
+            help_context: 0,
+        }
+    }
+
+    /// Creates a `ComError` with the error information of the current thread.
+    ///
+    /// The error information of the current thread is cleared.
+    /// Use [`ComError::from_ptr`] when the object that failed is known.
+    pub fn from_error_info(hr: HResult) -> ComError {
+        imp::take(hr).unwrap_or_else(|| ComError::new(hr))
+    }
+
+    /// Creates a `ComError` for a failure of a method of `ptr`.
+    ///
+    /// The error information of the current thread is retrieved only when the object supports
+    /// `ISupportErrorInfo` and reports that `T` supports the error information. Otherwise, the
+    /// error information is cleared because it was set by another call.
+    pub fn from_ptr<T: Interface>(hr: HResult, ptr: &ComPtr<T>) -> ComError {
+        let supported = ptr
+            .query_interface::<ISupportErrorInfo>()
+            .map(|p| unsafe { p.InterfaceSupportsErrorInfo(&T::uuidof()) } == S_OK)
+            .unwrap_or(false);
+        if supported {
+            ComError::from_error_info(hr)
+        } else {
+            imp::take(hr);
+            ComError::new(hr)
+        }
+    }
+
+    /// Sets the description.
+    #[inline]
+    pub fn with_description(mut self, description: impl Into<String>) -> ComError {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the source such as the ProgID of the class.
+    #[inline]
+    pub fn with_source(mut self, source: impl Into<String>) -> ComError {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sets the GUID of the interface that defined the error.
+    #[inline]
+    pub fn with_guid(mut self, guid: GUID) -> ComError {
+        self.guid = Some(guid);
+        self
+    }
+
+    /// Sets the help file and the help context.
+    #[inline]
+    pub fn with_help_file(mut self, help_file: impl Into<String>, help_context: u32) -> ComError {
+        self.help_file = Some(help_file.into());
+        self.help_context = help_context;
+        self
+    }
+
+    /// Sets the error information of the current thread like `SetErrorInfo`.
+    ///
+    /// COM objects implemented in Rust call this before returning a failure.
+    pub fn set_error_info(&self) -> Result<(), HResult> {
+        imp::set(self)
+    }
+
+    #[inline]
+    pub fn hresult(&self) -> HResult {
+        self.hr
+    }
+
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns the source such as the ProgID of the class.
+    ///
+    /// This is not `Error::source`, which returns the underlying error.
+    #[inline]
+    pub fn source_name(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    #[inline]
+    pub fn guid(&self) -> Option<&GUID> {
+        self.guid.as_ref()
+    }
+
+    #[inline]
+    pub fn help_file(&self) -> Option<&str> {
+        self.help_file.as_deref()
+    }
+
+    #[inline]
+    pub fn help_context(&self) -> u32 {
+        self.help_context
+    }
+}
+
+impl From<HResult> for ComError {
+    #[inline]
+    fn from(src: HResult) -> ComError {
+        ComError::new(src)
+    }
+}
+
+impl From<ComError> for HResult {
+    #[inline]
+    fn from(src: ComError) -> HResult {
+        src.hr
+    }
+}
+
+impl std::fmt::Debug for ComError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ComError")
+            .field("hresult", &self.hr)
+            .field("description", &self.description)
+            .field("source", &self.source)
+            .field("guid", &self.guid.as_ref().map(Guid::from_guid))
+            .field("help_file", &self.help_file)
+            .field("help_context", &self.help_context)
+            .finish()
+    }
+}
+
+/// Displays as `Source: Description (0x80020009)` when the error information has the description.
+/// Otherwise, displays the HResult.
+impl std::fmt::Display for ComError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (&self.source, &self.description) {
+            (Some(source), Some(description)) => {
+                write!(f, "{}: {} (0x{:08X})", source, description, self.hr.0)
+            }
+            (None, Some(description)) => write!(f, "{} (0x{:08X})", description, self.hr.0),
+            _ => std::fmt::Display::fmt(&self.hr, f),
+        }
+    }
+}
+
+impl std::error::Error for ComError {}
+
+#[cfg(windows)]
+mod imp {
+    use super::ComError;
+    use crate::sys::*;
+    use crate::{hresult, ComPtr, HResult};
+    use std::ptr::null_mut;
+    use winapi::shared::wtypes::BSTR;
+    use winapi::um::oaidl::{ICreateErrorInfo, IErrorInfo};
+    use winapi::um::oleauto::*;
+
+    const GUID_NULL: GUID = GUID {
+        Data1: 0,
+        Data2: 0,
+        Data3: 0,
+        Data4: [0; 8],
+    };
+
+    unsafe fn bstr<F>(f: F) -> Option<String>
+    where
+        F: FnOnce(*mut BSTR) -> HRESULT,
+    {
+        let mut s: BSTR = null_mut();
+        if f(&mut s) < 0 || s.is_null() {
+            return None;
+        }
+        let buffer = std::slice::from_raw_parts(s, SysStringLen(s) as usize);
+        let text = String::from_utf16_lossy(buffer);
+        SysFreeString(s);
+        let text = text.trim_end();
+        if text.is_empty() {
+            None
+        } else {
+            Some(text.to_string())
+        }
+    }
+
+    fn to_wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+
+    pub fn take(hr: HResult) -> Option<ComError> {
+        let mut p = null_mut();
+        let res = unsafe { GetErrorInfo(0, &mut p) };
+        if res != S_OK || p.is_null() {
+            return None;
+        }
+        let info = unsafe { ComPtr::<IErrorInfo>::from_raw(p) };
+        let mut e = ComError::new(hr);
+        unsafe {
+            let mut guid = GUID_NULL;
+            if info.GetGUID(&mut guid) == S_OK && !IsEqualGUID(&guid, &GUID_NULL) {
+                e.guid = Some(guid);
+            }
+            e.description = bstr(|p| info.GetDescription(p));
+            e.source = bstr(|p| info.GetSource(p));
+            e.help_file = bstr(|p| info.GetHelpFile(p));
+            let mut help_context = 0;
+            if info.GetHelpContext(&mut help_context) == S_OK {
+                e.help_context = help_context;
+            }
+        }
+        Some(e)
+    }
+
+    pub fn set(e: &ComError) -> Result<(), HResult> {
+        let info = unsafe { ComPtr::<ICreateErrorInfo>::from_out(|p| CreateErrorInfo(p))? };
+        unsafe {
+            if let Some(guid) = &e.guid {
+                hresult((), info.SetGUID(guid))?;
+            }
+            if let Some(description) = &e.description {
+                hresult((), info.SetDescription(to_wide(description).as_mut_ptr()))?;
+            }
+            if let Some(source) = &e.source {
+                hresult((), info.SetSource(to_wide(source).as_mut_ptr()))?;
+            }
+            if let Some(help_file) = &e.help_file {
+                hresult((), info.SetHelpFile(to_wide(help_file).as_mut_ptr()))?;
+            }
+            hresult((), info.SetHelpContext(e.help_context))?;
+            let info = info.query_interface::<IErrorInfo>()?;
+            hresult((), SetErrorInfo(0, info.as_ptr()))
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod imp {
+    use super::ComError;
+    use crate::HResult;
+    use std::cell::RefCell;
+
+    thread_local! {
+        static ERROR_INFO: RefCell<Option<ComError>> = const { RefCell::new(None) };
+    }
+
+    pub fn take(hr: HResult) -> Option<ComError> {
+        ERROR_INFO.with(|info| {
+            info.borrow_mut().take().map(|mut e| {
+                e.hr = hr;
+                e
+            })
+        })
+    }
+
+    pub fn set(e: &ComError) -> Result<(), HResult> {
+        ERROR_INFO.with(|info| *info.borrow_mut() = Some(e.clone()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::{com_class, com_impl, ComApartment};
+
+    com_interface! {
+        #[uuid(0x3e6a1f0b, 0x9c42, 0x4d85, 0xa7, 0x1e, 0x5b, 0x20, 0xc8, 0x93, 0x4f, 0x61)]
+        interface IAutomation(IAutomationVtbl): IUnknown(IUnknownVtbl) {
+            fn Invoke() -> HRESULT,
+        }
+    }
+
+    com_interface! {
+        #[uuid(0x7f14c2d9, 0x0b3e, 0x4a68, 0x95, 0x2c, 0xe1, 0x47, 0x6d, 0xa0, 0x3b, 0x88)]
+        interface IPlain(IPlainVtbl): IUnknown(IUnknownVtbl) {
+            fn Call() -> HRESULT,
+        }
+    }
+
+    #[com_class(IAutomation, IPlain, ISupportErrorInfo)]
+    struct Server;
+
+    impl Server {
+        fn fail() -> HRESULT {
+            ComError::new(HResult::DISP_E_EXCEPTION)
+                .with_description("Something went wrong.")
+                .with_source("Test.Server")
+                .with_guid(IAutomation::uuidof())
+                .with_help_file("server.chm", 42)
+                .set_error_info()
+                .unwrap();
+            HResult::DISP_E_EXCEPTION.0
+        }
+    }
+
+    #[com_impl(IAutomation)]
+    impl Server {
+        fn Invoke(&self) -> HRESULT {
+            Server::fail()
+        }
+    }
+
+    #[com_impl(IPlain)]
+    impl Server {
+        fn Call(&self) -> HRESULT {
+            Server::fail()
+        }
+    }
+
+    #[com_impl(ISupportErrorInfo)]
+    impl Server {
+        fn InterfaceSupportsErrorInfo(&self, riid: REFIID) -> HRESULT {
+            if unsafe { IsEqualGUID(&*riid, &IAutomation::uuidof()) } {
+                S_OK
+            } else {
+                S_FALSE
+            }
+        }
+    }
+
+    #[test]
+    fn error_info_test() {
+        let _apartment = ComApartment::mta().unwrap();
+        let p = Server.into_com_ptr();
+        let hr = HResult(unsafe { p.Invoke() });
+        let e = ComError::from_ptr(hr, &p);
+        assert_eq!(e.hresult(), HResult::DISP_E_EXCEPTION);
+        assert_eq!(e.description(), Some("Something went wrong."));
+        assert_eq!(e.source_name(), Some("Test.Server"));
+        assert!(IsEqualGUID(e.guid().unwrap(), &IAutomation::uuidof()));
+        assert_eq!(e.help_file(), Some("server.chm"));
+        assert_eq!(e.help_context(), 42);
+        assert_eq!(
+            e.to_string(),
+            "Test.Server: Something went wrong. (0x80020009)"
+        );
+        let e = ComError::from_error_info(hr);
+        assert_eq!(e.description(), None);
+    }
+
+    #[test]
+    fn unsupported_test() {
+        let _apartment = ComApartment::mta().unwrap();
+        let p = Server.into_com_ptr().query_interface::<IPlain>().unwrap();
+        let hr = HResult(unsafe { p.Call() });
+        let e = ComError::from_ptr(hr, &p);
+        assert_eq!(e.hresult(), HResult::DISP_E_EXCEPTION);
+        assert_eq!(e.description(), None);
+        assert_eq!(e.to_string(), HResult::DISP_E_EXCEPTION.to_string());
+        let e = ComError::from_error_info(hr);
+        assert_eq!(e.description(), None);
+    }
+
+    #[test]
+    fn debug_test() {
+        let e = ComError::new(HResult::E_FAIL).with_guid(IAutomation::uuidof());
+        assert!(format!("{:?}", e).contains("{3E6A1F0B-9C42-4D85-A71E-5B20C8934F61}"));
+    }
+
+    #[test]
+    fn question_mark_test() {
+        fn f() -> Result<(), HResult> {
+            Err(HResult::E_FAIL)
+        }
+        fn g() -> Result<(), ComError> {
+            f()?;
+            Ok(())
+        }
+        fn h() -> Result<(), HResult> {
+            g()?;
+            Ok(())
+        }
+        assert_eq!(g().unwrap_err().hresult(), HResult::E_FAIL);
+        assert_eq!(h(), Err(HResult::E_FAIL));
+    }
+}
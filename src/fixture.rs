@@ -0,0 +1,88 @@
+//! An interface, a class and a null object shared by the tests.
+
+use crate::sys::*;
+use crate::{com_class, com_impl, Agile, ComRef};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+com_interface! {
+    #[uuid(0x5e4b9a12, 0x7c3d, 0x4f60, 0xa1, 0x8e, 0x2d, 0x47, 0xb0, 0x96, 0xe3, 0x15)]
+    interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+        fn Get() -> u32,
+    }
+}
+
+unsafe impl Agile for IValue {}
+
+/// Implements `IValue` and sets `dropped` when the object is destroyed.
+#[com_class(IValue)]
+pub(crate) struct Value {
+    value: AtomicU32,
+    dropped: Arc<AtomicBool>,
+}
+
+impl Value {
+    pub(crate) fn new(value: u32) -> Self {
+        Self::with_dropped(value, Arc::default())
+    }
+
+    pub(crate) fn with_dropped(value: u32, dropped: Arc<AtomicBool>) -> Self {
+        Self {
+            value: AtomicU32::new(value),
+            dropped,
+        }
+    }
+}
+
+#[com_impl(IValue)]
+#[allow(non_snake_case)]
+impl Value {
+    fn Get(&self) -> u32 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Returns the reference count of the object.
+pub(crate) fn count<'a, T: Interface + 'a>(p: impl Into<ComRef<'a, T>>) -> u32 {
+    unsafe {
+        let unknown = &*(p.into().as_ptr() as *mut IUnknown);
+        unknown.AddRef();
+        unknown.Release()
+    }
+}
+
+unsafe extern "system" fn null_query_interface(
+    _: *mut IUnknown,
+    _: REFIID,
+    ppv: *mut *mut c_void,
+) -> HRESULT {
+    *ppv = std::ptr::null_mut();
+    S_OK
+}
+
+unsafe extern "system" fn null_add_ref(_: *mut IUnknown) -> ULONG {
+    1
+}
+
+unsafe extern "system" fn null_release(_: *mut IUnknown) -> ULONG {
+    1
+}
+
+static NULL_VTBL: IUnknownVtbl = IUnknownVtbl {
+    QueryInterface: null_query_interface,
+    AddRef: null_add_ref,
+    Release: null_release,
+};
+
+/// Returns an object whose `QueryInterface` returns `S_OK` with a null interface.
+///
+/// The object is not reference counted, so it is valid while it is alive.
+pub(crate) fn null_object() -> IUnknown {
+    IUnknown { lpVtbl: &NULL_VTBL }
+}
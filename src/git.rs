@@ -0,0 +1,376 @@
+//! Handing off interfaces between apartments.
+//!
+//! On Windows, [`GitCookie`] uses the Global Interface Table.
+//! With [`Backend::Emulated`], which is the only backend on other platforms, the table is
+//! emulated in the process. The emulated table cannot marshal interfaces, so the interfaces
+//! registered by [`GitCookie::new`] can be retrieved only on the registering thread.
+//! [`GitCookie::new_agile`] registers an [`Agile`] interface that can be retrieved on any thread.
+
+use crate::sys::*;
+use crate::{Agile, Backend, ComPtr, HResult};
+use std::marker::PhantomData;
+
+/// A cookie of an interface registered in the Global Interface Table.
+///
+/// `GitCookie<T>` is `Send` and `Sync` even if `ComPtr<T>` is not, and produces a new `ComPtr<T>`
+/// in the apartment of the thread that calls `get`. The interface is revoked when the cookie is dropped.
+pub struct GitCookie<T: Interface> {
+    cookie: DWORD,
+    table: Table,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Interface> GitCookie<T> {
+    /// Registers an interface in the Global Interface Table.
+    ///
+    /// With [`Backend::Emulated`], `get` on other threads than the registering thread returns
+    /// `RPC_E_WRONG_THREAD`.
+    pub fn new(ptr: &ComPtr<T>) -> Result<GitCookie<T>, HResult> {
+        GitCookie::register(ptr, false)
+    }
+
+    /// Registers an agile interface in the Global Interface Table.
+    ///
+    /// The interface can be retrieved on any thread with all backends.
+    pub fn new_agile(ptr: &ComPtr<T>) -> Result<GitCookie<T>, HResult>
+    where
+        T: Agile,
+    {
+        GitCookie::register(ptr, true)
+    }
+
+    fn register(ptr: &ComPtr<T>, agile: bool) -> Result<GitCookie<T>, HResult> {
+        let table = Table::current()?;
+        let cookie = table.register(ptr.as_ptr() as *mut IUnknown, &T::uuidof(), agile)?;
+        Ok(GitCookie {
+            cookie,
+            table,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the interface for the apartment of the current thread.
+    pub fn get(&self) -> Result<ComPtr<T>, HResult> {
+        self.table.get(self.cookie)?.query_interface::<T>()
+    }
+
+    /// Returns the value of the cookie.
+    #[inline]
+    pub fn cookie(&self) -> DWORD {
+        self.cookie
+    }
+
+    /// Revokes the interface and returns the result that `Drop` ignores.
+    ///
+    /// With [`Backend::Emulated`], a non-agile interface revoked on another thread is released on
+    /// the registering thread when the thread uses the table next time or exits.
+    pub fn revoke(mut self) -> Result<(), HResult> {
+        let cookie = std::mem::replace(&mut self.cookie, 0);
+        self.table.revoke(cookie)
+    }
+}
+
+impl<T: Interface> Drop for GitCookie<T> {
+    fn drop(&mut self) {
+        if self.cookie != 0 {
+            self.table.revoke(self.cookie).ok();
+        }
+    }
+}
+
+impl<T: Interface> std::fmt::Debug for GitCookie<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("GitCookie").field(&self.cookie).finish()
+    }
+}
+
+/// The table that registered the interface.
+///
+/// The Global Interface Table is kept to revoke the interface on any thread and backend.
+enum Table {
+    #[cfg(windows)]
+    Com(crate::ThreadSafe<winapi::um::objidlbase::IGlobalInterfaceTable>),
+    Emulated,
+}
+
+impl Table {
+    fn current() -> Result<Table, HResult> {
+        match Backend::current() {
+            #[cfg(windows)]
+            Backend::Com => imp::table().map(Table::Com),
+            _ => Ok(Table::Emulated),
+        }
+    }
+
+    fn register(&self, p: *mut IUnknown, iid: &IID, agile: bool) -> Result<DWORD, HResult> {
+        match self {
+            #[cfg(windows)]
+            Table::Com(table) => imp::register(table, p, iid),
+            Table::Emulated => emulated::register(p, iid, agile),
+        }
+    }
+
+    fn get(&self, cookie: DWORD) -> Result<ComPtr<IUnknown>, HResult> {
+        match self {
+            #[cfg(windows)]
+            Table::Com(table) => imp::get(table, cookie),
+            Table::Emulated => emulated::get(cookie),
+        }
+    }
+
+    fn revoke(&self, cookie: DWORD) -> Result<(), HResult> {
+        match self {
+            #[cfg(windows)]
+            Table::Com(table) => imp::revoke(table, cookie),
+            Table::Emulated => emulated::revoke(cookie),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use crate::sys::*;
+    use crate::{hresult, ComPtr, HResult, ThreadSafe};
+    use std::ptr::null_mut;
+    use winapi::shared::wtypesbase::CLSCTX_INPROC_SERVER;
+    use winapi::um::cguid::CLSID_StdGlobalInterfaceTable;
+    use winapi::um::combaseapi::CoCreateInstance;
+    use winapi::um::objidlbase::IGlobalInterfaceTable;
+
+    pub fn table() -> Result<ThreadSafe<IGlobalInterfaceTable>, HResult> {
+        let table = unsafe {
+            ComPtr::from_iid_out(|iid, p| {
+                CoCreateInstance(
+                    &CLSID_StdGlobalInterfaceTable,
+                    null_mut(),
+                    CLSCTX_INPROC_SERVER,
+                    iid,
+                    p,
+                )
+            })?
+        };
+        // The Global Interface Table is free-threaded.
+        Ok(unsafe { ThreadSafe::new(table) })
+    }
+
+    pub fn register(
+        table: &IGlobalInterfaceTable,
+        p: *mut IUnknown,
+        iid: &IID,
+    ) -> Result<DWORD, HResult> {
+        let mut cookie = 0;
+        let res = unsafe { table.RegisterInterfaceInGlobal(p, iid, &mut cookie) };
+        hresult(cookie, res)
+    }
+
+    pub fn get(table: &IGlobalInterfaceTable, cookie: DWORD) -> Result<ComPtr<IUnknown>, HResult> {
+        unsafe { ComPtr::from_iid_out(|iid, p| table.GetInterfaceFromGlobal(cookie, iid, p)) }
+    }
+
+    pub fn revoke(table: &IGlobalInterfaceTable, cookie: DWORD) -> Result<(), HResult> {
+        let res = unsafe { table.RevokeInterfaceFromGlobal(cookie) };
+        hresult((), res)
+    }
+}
+
+mod emulated {
+    use crate::sys::*;
+    use crate::{ComPtr, HResult, ThreadSafe};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread::ThreadId;
+
+    struct Entry {
+        p: ThreadSafe<IUnknown>,
+        thread: ThreadId,
+        agile: bool,
+    }
+
+    impl Entry {
+        fn is_available(&self, thread: ThreadId) -> bool {
+            self.agile || self.thread == thread
+        }
+    }
+
+    // The entries are shared by `Arc`, so that `get` calls `AddRef` without the lock.
+    static TABLE: Mutex<BTreeMap<DWORD, Arc<Entry>>> = Mutex::new(BTreeMap::new());
+    static NEXT_COOKIE: AtomicU32 = AtomicU32::new(1);
+
+    // The entries that were revoked on other threads than the registering thread.
+    // They are released when the registering thread uses the table next time or exits.
+    static REVOKED: Mutex<Vec<Arc<Entry>>> = Mutex::new(Vec::new());
+
+    /// Releases the non-agile entries of the thread when the thread exits.
+    struct ThreadGuard(ThreadId);
+
+    impl Drop for ThreadGuard {
+        fn drop(&mut self) {
+            release_revoked(self.0);
+            let entries = {
+                let mut table = TABLE.lock().unwrap();
+                let cookies = table
+                    .iter()
+                    .filter(|(_, e)| !e.agile && e.thread == self.0)
+                    .map(|(&cookie, _)| cookie)
+                    .collect::<Vec<_>>();
+                cookies
+                    .iter()
+                    .filter_map(|cookie| table.remove(cookie))
+                    .collect::<Vec<_>>()
+            };
+            drop(entries);
+        }
+    }
+
+    thread_local! {
+        static GUARD: ThreadGuard = ThreadGuard(std::thread::current().id());
+    }
+
+    fn release_revoked(thread: ThreadId) {
+        let entries = {
+            let mut revoked = REVOKED.lock().unwrap();
+            let (entries, rest) = revoked
+                .drain(..)
+                .partition(|e: &Arc<Entry>| e.is_available(thread));
+            *revoked = rest;
+            entries
+        };
+        drop(entries);
+    }
+
+    pub fn register(p: *mut IUnknown, _iid: &IID, agile: bool) -> Result<DWORD, HResult> {
+        let thread = std::thread::current().id();
+        release_revoked(thread);
+        if p.is_null() {
+            return Err(HResult(E_INVALIDARG));
+        }
+        if !agile {
+            GUARD.with(|_| {});
+        }
+        let p = unsafe {
+            (*p).AddRef();
+            ComPtr::from_raw(p)
+        };
+        let cookie = NEXT_COOKIE.fetch_add(1, Ordering::Relaxed);
+        let entry = Entry {
+            // The entries of non-agile interfaces are used and released only on the registering
+            // thread.
+            p: unsafe { ThreadSafe::new(p) },
+            thread,
+            agile,
+        };
+        TABLE.lock().unwrap().insert(cookie, Arc::new(entry));
+        Ok(cookie)
+    }
+
+    pub fn get(cookie: DWORD) -> Result<ComPtr<IUnknown>, HResult> {
+        let thread = std::thread::current().id();
+        release_revoked(thread);
+        let entry = {
+            let table = TABLE.lock().unwrap();
+            let entry = table.get(&cookie).ok_or(HResult(E_INVALIDARG))?;
+            if !entry.is_available(thread) {
+                return Err(HResult::RPC_E_WRONG_THREAD);
+            }
+            entry.clone()
+        };
+        Ok(ComPtr::clone(&entry.p))
+    }
+
+    pub fn revoke(cookie: DWORD) -> Result<(), HResult> {
+        let thread = std::thread::current().id();
+        release_revoked(thread);
+        let entry = TABLE.lock().unwrap().remove(&cookie);
+        let entry = entry.ok_or(HResult(E_INVALIDARG))?;
+        if !entry.is_available(thread) {
+            REVOKED.lock().unwrap().push(entry);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{IValue, Value};
+    use crate::ComApartment;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn handoff_test() {
+        let _apartment = ComApartment::mta().unwrap();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let p = Value::with_dropped(5, dropped.clone()).into_com_ptr();
+        let cookie = GitCookie::new_agile(&p).unwrap();
+        drop(p);
+        assert!(!dropped.load(Ordering::Relaxed));
+        let cookie = std::thread::spawn(move || {
+            let _apartment = ComApartment::mta().unwrap();
+            let q = cookie.get().unwrap();
+            assert_eq!(unsafe { q.Get() }, 5);
+            cookie
+        })
+        .join()
+        .unwrap();
+        drop(cookie);
+        assert!(dropped.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn wrong_thread_test() {
+        std::thread::spawn(|| {
+            #[cfg(windows)]
+            Backend::select(Backend::Emulated);
+            let _apartment = ComApartment::mta().unwrap();
+            let dropped = Arc::new(AtomicBool::new(false));
+            let p = Value::with_dropped(5, dropped.clone()).into_com_ptr();
+            let cookie = GitCookie::new(&p).unwrap();
+            drop(p);
+            assert_eq!(unsafe { cookie.get().unwrap().Get() }, 5);
+            let cookie = std::thread::spawn(move || {
+                assert_eq!(cookie.get().err(), Some(HResult::RPC_E_WRONG_THREAD));
+                cookie.revoke().unwrap();
+            });
+            cookie.join().unwrap();
+            assert!(!dropped.load(Ordering::Relaxed));
+            let other = GitCookie::new(&Value::new(0).into_com_ptr());
+            assert!(dropped.load(Ordering::Relaxed));
+            assert_eq!(other.unwrap().revoke(), Ok(()));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn thread_exit_test() {
+        fn register(dropped: &Arc<AtomicBool>) -> GitCookie<IValue> {
+            #[cfg(windows)]
+            Backend::select(Backend::Emulated);
+            let _apartment = ComApartment::mta().unwrap();
+            GitCookie::new(&Value::with_dropped(5, dropped.clone()).into_com_ptr()).unwrap()
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let d = dropped.clone();
+        let cookie = std::thread::spawn(move || register(&d)).join().unwrap();
+        assert!(dropped.load(Ordering::Relaxed));
+        assert_eq!(cookie.revoke(), Err(HResult(E_INVALIDARG)));
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let d = dropped.clone();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            tx.send(register(&d)).unwrap();
+            done_rx.recv().unwrap();
+            assert!(!d.load(Ordering::Relaxed));
+        });
+        rx.recv().unwrap().revoke().unwrap();
+        assert!(!dropped.load(Ordering::Relaxed));
+        done_tx.send(()).unwrap();
+        thread.join().unwrap();
+        assert!(dropped.load(Ordering::Relaxed));
+    }
+}
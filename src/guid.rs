@@ -0,0 +1,294 @@
+//! A portable GUID.
+
+use crate::sys::GUID;
+
+/// A GUID that is layout-compatible with `GUID`.
+///
+/// `Guid` is displayed as `6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52`, and as the registry format
+/// `{6B0D26A1-2A33-4E5C-9B3F-416C8D0E7A52}` with `{:#}`. Both formats are parsed by `FromStr`.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Guid {
+    pub data1: u32,
+    pub data2: u16,
+    pub data3: u16,
+    pub data4: [u8; 8],
+}
+
+impl Guid {
+    /// `{00000000-0000-0000-0000-000000000000}`
+    pub const NULL: Guid = Guid::from_u128(0);
+
+    /// Creates a `Guid` from the fields.
+    #[inline]
+    pub const fn new(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Guid {
+        Guid {
+            data1,
+            data2,
+            data3,
+            data4,
+        }
+    }
+
+    /// Creates a `Guid` from a `u128` such as `0x6b0d26a1_2a33_4e5c_9b3f_416c8d0e7a52`.
+    #[inline]
+    pub const fn from_u128(value: u128) -> Guid {
+        Guid {
+            data1: (value >> 96) as u32,
+            data2: (value >> 80) as u16,
+            data3: (value >> 64) as u16,
+            data4: (value as u64).to_be_bytes(),
+        }
+    }
+
+    /// Returns the value as a `u128`.
+    #[inline]
+    pub const fn to_u128(&self) -> u128 {
+        ((self.data1 as u128) << 96)
+            | ((self.data2 as u128) << 80)
+            | ((self.data3 as u128) << 64)
+            | u64::from_be_bytes(self.data4) as u128
+    }
+
+    /// Creates a `Guid` from a `GUID`.
+    #[inline]
+    pub const fn from_guid(guid: &GUID) -> Guid {
+        Guid {
+            data1: guid.Data1,
+            data2: guid.Data2,
+            data3: guid.Data3,
+            data4: guid.Data4,
+        }
+    }
+
+    /// Returns the value as a `GUID`.
+    #[inline]
+    pub const fn to_guid(&self) -> GUID {
+        GUID {
+            Data1: self.data1,
+            Data2: self.data2,
+            Data3: self.data3,
+            Data4: self.data4,
+        }
+    }
+
+    /// Parses a string in the format of `6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52`
+    /// or `{6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52}` in const contexts.
+    pub const fn parse(s: &str) -> Result<Guid, ParseGuidError> {
+        let s = s.as_bytes();
+        let (start, len) = match s.len() {
+            36 => (0, 36),
+            38 if s[0] == b'{' && s[37] == b'}' => (1, 36),
+            _ => return Err(ParseGuidError(())),
+        };
+        let mut value: u128 = 0;
+        let mut i = 0;
+        while i < len {
+            let c = s[start + i];
+            if i == 8 || i == 13 || i == 18 || i == 23 {
+                if c != b'-' {
+                    return Err(ParseGuidError(()));
+                }
+            } else {
+                let digit = match c {
+                    b'0'..=b'9' => c - b'0',
+                    b'a'..=b'f' => c - b'a' + 10,
+                    b'A'..=b'F' => c - b'A' + 10,
+                    _ => return Err(ParseGuidError(())),
+                };
+                value = (value << 4) | digit as u128;
+            }
+            i += 1;
+        }
+        Ok(Guid::from_u128(value))
+    }
+
+    #[doc(hidden)]
+    pub const fn parse_or_panic(s: &str) -> Guid {
+        match Guid::parse(s) {
+            Ok(guid) => guid,
+            Err(_) => panic!("invalid GUID"),
+        }
+    }
+}
+
+/// Creates a [`Guid`] from a string literal. The string is validated at compile time.
+///
+/// ```
+/// use com_ptr::{guid, Guid};
+///
+/// const IID_IVALUE: Guid = guid!("6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52");
+/// assert_eq!(IID_IVALUE, Guid::from_u128(0x6b0d26a1_2a33_4e5c_9b3f_416c8d0e7a52));
+/// assert_eq!(guid!("{6B0D26A1-2A33-4E5C-9B3F-416C8D0E7A52}"), IID_IVALUE);
+/// ```
+///
+/// ```compile_fail
+/// let _ = com_ptr::guid!("6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a5");
+/// ```
+#[macro_export]
+macro_rules! guid {
+    ($s:expr) => {{
+        const GUID: $crate::Guid = $crate::Guid::parse_or_panic($s);
+        GUID
+    }};
+}
+
+impl From<GUID> for Guid {
+    #[inline]
+    fn from(src: GUID) -> Guid {
+        Guid::from_guid(&src)
+    }
+}
+
+impl From<Guid> for GUID {
+    #[inline]
+    fn from(src: Guid) -> GUID {
+        src.to_guid()
+    }
+}
+
+impl std::fmt::Display for Guid {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let d = &self.data4;
+        if f.alternate() {
+            write!(
+                f,
+                "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+                self.data1, self.data2, self.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+            )
+        } else {
+            write!(
+                f,
+                "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+                self.data1, self.data2, self.data3, d[0], d[1], d[2], d[3], d[4], d[5], d[6], d[7]
+            )
+        }
+    }
+}
+
+impl std::fmt::Debug for Guid {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:#}", self)
+    }
+}
+
+/// An error which can be returned when parsing a Guid.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParseGuidError(());
+
+impl std::fmt::Display for ParseGuidError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid GUID")
+    }
+}
+
+impl std::error::Error for ParseGuidError {}
+
+impl std::str::FromStr for Guid {
+    type Err = ParseGuidError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Guid, ParseGuidError> {
+        Guid::parse(s.trim())
+    }
+}
+
+/// Serialized as the string such as `6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Guid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Guid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Guid, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Guid;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a GUID string")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Guid, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::IsEqualGUID;
+
+    const IID: Guid = guid!("6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52");
+
+    #[test]
+    fn parse_test() {
+        let guid = Guid::new(
+            0x6b0d26a1,
+            0x2a33,
+            0x4e5c,
+            [0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52],
+        );
+        assert_eq!(IID, guid);
+        assert_eq!("6B0D26A1-2A33-4E5C-9B3F-416C8D0E7A52".parse(), Ok(guid));
+        assert_eq!("{6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52}".parse(), Ok(guid));
+        assert!("6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a5".parse::<Guid>().is_err());
+        assert!("6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a5g".parse::<Guid>().is_err());
+        assert!("6b0d26a1+2a33-4e5c-9b3f-416c8d0e7a52".parse::<Guid>().is_err());
+        assert!("{6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52".parse::<Guid>().is_err());
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(IID.to_string(), "6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52");
+        assert_eq!(format!("{:#}", IID), "{6B0D26A1-2A33-4E5C-9B3F-416C8D0E7A52}");
+        assert_eq!(format!("{:?}", Guid::NULL), "{00000000-0000-0000-0000-000000000000}");
+        assert_eq!(IID.to_string().parse(), Ok(IID));
+        assert_eq!(format!("{:#}", IID).parse(), Ok(IID));
+    }
+
+    #[test]
+    fn u128_test() {
+        let value = 0x6b0d26a1_2a33_4e5c_9b3f_416c8d0e7a52;
+        assert_eq!(Guid::from_u128(value), IID);
+        assert_eq!(IID.to_u128(), value);
+        assert!(Guid::NULL < IID);
+    }
+
+    #[test]
+    fn guid_test() {
+        let guid: GUID = IID.into();
+        assert_eq!(guid.Data1, 0x6b0d26a1);
+        assert_eq!(guid.Data4, [0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52]);
+        assert_eq!(Guid::from(guid), IID);
+        assert!(IsEqualGUID(&IID.to_guid(), &guid));
+        assert_eq!(std::mem::size_of::<Guid>(), std::mem::size_of::<GUID>());
+        assert_eq!(std::mem::align_of::<Guid>(), std::mem::align_of::<GUID>());
+    }
+
+    #[test]
+    fn hash_test() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(IID);
+        set.insert(Guid::NULL);
+        set.insert(guid!("{6B0D26A1-2A33-4E5C-9B3F-416C8D0E7A52}"));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_test() {
+        let json = serde_json::to_string(&IID).unwrap();
+        assert_eq!(json, "\"6b0d26a1-2a33-4e5c-9b3f-416c8d0e7a52\"");
+        assert_eq!(serde_json::from_str::<Guid>(&json).unwrap(), IID);
+        assert!(serde_json::from_str::<Guid>("\"invalid\"").is_err());
+    }
+}
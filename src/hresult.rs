@@ -0,0 +1,442 @@
+//! HRESULT.
+
+use crate::sys::HRESULT;
+
+/// A object that wraps HRESULT.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(transparent)]
+pub struct HResult(pub HRESULT);
+
+/// The severity of HRESULT.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Severity {
+    Success,
+    Error,
+}
+
+macro_rules! facilities {
+    ($($(#[$attr:meta])* $name:ident = $value:expr,)*) => {
+        /// The facility of HRESULT.
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub enum Facility {
+            $($(#[$attr])* $name,)*
+            /// A facility that is not listed.
+            Other(OtherFacility),
+        }
+
+        impl Facility {
+            /// Returns a `Facility` from the value.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `value` is greater than `0x1fff`, which does not fit in the facility
+            /// bits of HRESULT.
+            pub const fn from_u16(value: u16) -> Facility {
+                assert!(value <= FACILITY_MASK, "the facility is greater than 0x1fff");
+                match value {
+                    $($value => Facility::$name,)*
+                    _ => Facility::Other(OtherFacility(value)),
+                }
+            }
+
+            /// Returns the value of the facility.
+            pub const fn to_u16(self) -> u16 {
+                match self {
+                    $(Facility::$name => $value,)*
+                    Facility::Other(other) => other.0,
+                }
+            }
+        }
+    };
+}
+
+const FACILITY_MASK: u16 = 0x1fff;
+
+/// The value of a facility that is not listed in [`Facility`].
+///
+/// This is created only by [`Facility::from_u16`], so that `Facility::Other` never has the value
+/// of a listed facility or a value greater than `0x1fff`, and `Facility` is compared by `==` and
+/// matched correctly.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct OtherFacility(u16);
+
+impl OtherFacility {
+    /// Returns the value of the facility.
+    #[inline]
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+}
+
+facilities! {
+    Null = 0,
+    Rpc = 1,
+    Dispatch = 2,
+    Storage = 3,
+    Itf = 4,
+    Win32 = 7,
+    Windows = 8,
+    Security = 9,
+    Control = 10,
+    Cert = 11,
+    Internet = 12,
+    MediaServer = 13,
+    Msmq = 14,
+    SetupApi = 15,
+    SmartCard = 16,
+    ComPlus = 17,
+    Urt = 19,
+    Http = 25,
+    Graphics = 38,
+    Shell = 39,
+    Xaml = 43,
+    WindowsStore = 63,
+    Direct3D10 = 2169,
+    Dxgi = 2170,
+    DxgiDdi = 2171,
+    Direct3D11 = 2172,
+    Direct3D12 = 2174,
+    Direct3D12Debug = 2175,
+    /// WIC, DirectWrite and DWM.
+    WincodecDwriteDwm = 2200,
+    Direct2D = 2201,
+}
+
+/// Panics if the value is greater than `0x1fff` like [`Facility::from_u16`].
+impl From<u16> for Facility {
+    #[inline]
+    fn from(src: u16) -> Facility {
+        Facility::from_u16(src)
+    }
+}
+
+impl From<Facility> for u16 {
+    #[inline]
+    fn from(src: Facility) -> u16 {
+        src.to_u16()
+    }
+}
+
+impl HResult {
+    pub const S_OK: HResult = HResult(0);
+    pub const S_FALSE: HResult = HResult(1);
+    pub const E_NOTIMPL: HResult = HResult(0x80004001u32 as HRESULT);
+    pub const E_NOINTERFACE: HResult = HResult(0x80004002u32 as HRESULT);
+    pub const E_POINTER: HResult = HResult(0x80004003u32 as HRESULT);
+    pub const E_ABORT: HResult = HResult(0x80004004u32 as HRESULT);
+    pub const E_PENDING: HResult = HResult(0x8000000Au32 as HRESULT);
+    pub const E_FAIL: HResult = HResult(0x80004005u32 as HRESULT);
+    pub const E_UNEXPECTED: HResult = HResult(0x8000FFFFu32 as HRESULT);
+    pub const E_ACCESSDENIED: HResult = HResult(0x80070005u32 as HRESULT);
+    pub const E_HANDLE: HResult = HResult(0x80070006u32 as HRESULT);
+    pub const E_OUTOFMEMORY: HResult = HResult(0x8007000Eu32 as HRESULT);
+    pub const E_INVALIDARG: HResult = HResult(0x80070057u32 as HRESULT);
+    pub const STG_E_FILENOTFOUND: HResult = HResult(0x80030002u32 as HRESULT);
+    pub const STG_E_PATHNOTFOUND: HResult = HResult(0x80030003u32 as HRESULT);
+    pub const STG_E_ACCESSDENIED: HResult = HResult(0x80030005u32 as HRESULT);
+    pub const STG_E_INSUFFICIENTMEMORY: HResult = HResult(0x80030008u32 as HRESULT);
+    pub const STG_E_FILEALREADYEXISTS: HResult = HResult(0x80030050u32 as HRESULT);
+    pub const STG_E_INVALIDPARAMETER: HResult = HResult(0x80030057u32 as HRESULT);
+    pub const CLASS_E_NOAGGREGATION: HResult = HResult(0x80040110u32 as HRESULT);
+    pub const CLASS_E_CLASSNOTAVAILABLE: HResult = HResult(0x80040111u32 as HRESULT);
+    pub const REGDB_E_CLASSNOTREG: HResult = HResult(0x80040154u32 as HRESULT);
+    pub const CO_E_NOTINITIALIZED: HResult = HResult(0x800401F0u32 as HRESULT);
+    pub const RPC_E_CHANGED_MODE: HResult = HResult(0x80010106u32 as HRESULT);
+    pub const RPC_E_WRONG_THREAD: HResult = HResult(0x8001010Eu32 as HRESULT);
+    pub const DISP_E_EXCEPTION: HResult = HResult(0x80020009u32 as HRESULT);
+
+    /// Creates a HResult from the severity, the facility and the code like `MAKE_HRESULT`.
+    #[inline]
+    pub const fn new(severity: Severity, facility: Facility, code: u16) -> HResult {
+        let severity = match severity {
+            Severity::Success => 0,
+            Severity::Error => 1u32 << 31,
+        };
+        let facility = (facility.to_u16() as u32) << 16;
+        HResult((severity | facility | code as u32) as HRESULT)
+    }
+
+    /// Returns the severity like `HRESULT_SEVERITY`.
+    #[inline]
+    pub const fn severity(&self) -> Severity {
+        if self.0 < 0 {
+            Severity::Error
+        } else {
+            Severity::Success
+        }
+    }
+
+    /// Returns the facility like `HRESULT_FACILITY`.
+    #[inline]
+    pub const fn facility(&self) -> Facility {
+        Facility::from_u16((self.0 as u32 >> 16) as u16 & FACILITY_MASK)
+    }
+
+    /// Returns the code part like `HRESULT_CODE`.
+    #[inline]
+    pub const fn code_part(&self) -> u16 {
+        (self.0 as u32 & 0xffff) as u16
+    }
+
+    #[inline]
+    pub fn is_succeed(&self) -> bool {
+        self.0 >= 0
+    }
+    
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        self.0 < 0
+    }
+    
+    #[inline]
+    pub fn code(&self) -> HRESULT {
+        self.0
+    }
+}
+
+impl HResult {
+    /// Returns the symbolic name such as `E_ACCESSDENIED`.
+    #[cfg(feature = "hresult-table")]
+    #[inline]
+    pub fn symbol(&self) -> Option<&'static str> {
+        crate::table::find(self.0 as u32).map(|(symbol, _)| symbol)
+    }
+
+    /// Returns the message from the registered message sources.
+    ///
+    /// See [`message`](crate::message).
+    #[inline]
+    pub fn message(&self) -> Option<String> {
+        crate::message::lookup(*self)
+    }
+}
+
+/// With the `hresult-table` feature, the known HRESULTs are displayed as
+/// `E_ACCESSDENIED (0x80070005): Access is denied.` on all platforms, and as `S_FALSE (0x00000001)`
+/// when winerror.h has no message.
+impl std::fmt::Display for HResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        #[cfg(feature = "hresult-table")]
+        {
+            match crate::table::find(self.0 as u32) {
+                Some((symbol, "")) => return write!(f, "{} (0x{:08X})", symbol, self.0),
+                Some((symbol, message)) => {
+                    return write!(f, "{} (0x{:08X}): {}", symbol, self.0, message)
+                }
+                None => {}
+            }
+        }
+        match self.message() {
+            Some(message) => write!(f, "{}", message),
+            None => crate::message::fmt_fallback(*self, f),
+        }
+    }
+}
+
+impl std::error::Error for HResult {}
+
+/// Formats the value as an unsigned integer such as `0x80070005`.
+impl std::fmt::LowerHex for HResult {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::LowerHex::fmt(&(self.0 as u32), f)
+    }
+}
+
+/// Formats the value as an unsigned integer such as `0x80070005`.
+impl std::fmt::UpperHex for HResult {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::UpperHex::fmt(&(self.0 as u32), f)
+    }
+}
+
+/// The symbolic names of the associated constants.
+const NAMES: &[(&str, HResult)] = &[
+    ("S_OK", HResult::S_OK),
+    ("S_FALSE", HResult::S_FALSE),
+    ("E_NOTIMPL", HResult::E_NOTIMPL),
+    ("E_NOINTERFACE", HResult::E_NOINTERFACE),
+    ("E_POINTER", HResult::E_POINTER),
+    ("E_ABORT", HResult::E_ABORT),
+    ("E_PENDING", HResult::E_PENDING),
+    ("E_FAIL", HResult::E_FAIL),
+    ("E_UNEXPECTED", HResult::E_UNEXPECTED),
+    ("E_ACCESSDENIED", HResult::E_ACCESSDENIED),
+    ("E_HANDLE", HResult::E_HANDLE),
+    ("E_OUTOFMEMORY", HResult::E_OUTOFMEMORY),
+    ("E_INVALIDARG", HResult::E_INVALIDARG),
+    ("STG_E_FILENOTFOUND", HResult::STG_E_FILENOTFOUND),
+    ("STG_E_PATHNOTFOUND", HResult::STG_E_PATHNOTFOUND),
+    ("STG_E_ACCESSDENIED", HResult::STG_E_ACCESSDENIED),
+    ("STG_E_INSUFFICIENTMEMORY", HResult::STG_E_INSUFFICIENTMEMORY),
+    ("STG_E_FILEALREADYEXISTS", HResult::STG_E_FILEALREADYEXISTS),
+    ("STG_E_INVALIDPARAMETER", HResult::STG_E_INVALIDPARAMETER),
+    ("CLASS_E_NOAGGREGATION", HResult::CLASS_E_NOAGGREGATION),
+    ("CLASS_E_CLASSNOTAVAILABLE", HResult::CLASS_E_CLASSNOTAVAILABLE),
+    ("REGDB_E_CLASSNOTREG", HResult::REGDB_E_CLASSNOTREG),
+    ("CO_E_NOTINITIALIZED", HResult::CO_E_NOTINITIALIZED),
+    ("RPC_E_CHANGED_MODE", HResult::RPC_E_CHANGED_MODE),
+    ("RPC_E_WRONG_THREAD", HResult::RPC_E_WRONG_THREAD),
+    ("DISP_E_EXCEPTION", HResult::DISP_E_EXCEPTION),
+];
+
+/// An error which can be returned when parsing a HResult.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseHResultError(String);
+
+impl std::fmt::Display for ParseHResultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "invalid HRESULT: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseHResultError {}
+
+/// Parses a hexadecimal number such as `0x80070005`, a signed or unsigned decimal number
+/// such as `-2147024891` or `2147942405`, or a symbolic name such as `E_ACCESSDENIED`.
+///
+/// With the `hresult-table` feature, all symbolic names in the table are accepted.
+impl std::str::FromStr for HResult {
+    type Err = ParseHResultError;
+
+    fn from_str(s: &str) -> Result<HResult, ParseHResultError> {
+        let s = s.trim();
+        let err = || ParseHResultError(s.to_string());
+        if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            return u32::from_str_radix(hex, 16)
+                .map(|v| HResult(v as HRESULT))
+                .map_err(|_| err());
+        }
+        if s.starts_with(|c: char| c == '-' || c == '+' || c.is_ascii_digit()) {
+            if let Ok(v) = s.parse::<i32>() {
+                return Ok(HResult(v));
+            }
+            return s
+                .parse::<u32>()
+                .map(|v| HResult(v as HRESULT))
+                .map_err(|_| err());
+        }
+        if let Some(&(_, hr)) = NAMES.iter().find(|(name, _)| *name == s) {
+            return Ok(hr);
+        }
+        #[cfg(feature = "hresult-table")]
+        {
+            if let Some(&(value, _, _)) = crate::table::TABLE.iter().find(|e| e.1 == s) {
+                return Ok(HResult(value as HRESULT));
+            }
+        }
+        Err(err())
+    }
+}
+
+/// Returns a object when success.
+///
+/// If `res` is success, returns a object. OtherWise, returns a HResult object.
+pub fn hresult<T>(obj: T, res: HRESULT) -> Result<T, HResult> {
+    if res < 0 {
+        Err(HResult(res))
+    } else {
+        Ok(obj)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decompose_test() {
+        let hr = HResult::E_ACCESSDENIED;
+        assert_eq!(hr.severity(), Severity::Error);
+        assert_eq!(hr.facility(), Facility::Win32);
+        assert_eq!(hr.code_part(), 5);
+        let hr = HResult::S_FALSE;
+        assert_eq!(hr.severity(), Severity::Success);
+        assert_eq!(hr.facility(), Facility::Null);
+        assert_eq!(hr.code_part(), 1);
+        let hr = HResult(0x887A0005u32 as HRESULT);
+        assert_eq!(hr.facility(), Facility::Dxgi);
+        assert_eq!(HResult(0x80AB0001u32 as HRESULT).facility(), Facility::from_u16(0xAB));
+    }
+
+    #[test]
+    fn new_test() {
+        assert_eq!(
+            HResult::new(Severity::Error, Facility::Null, 0x4002),
+            HResult::E_NOINTERFACE
+        );
+        assert_eq!(
+            HResult::new(Severity::Error, Facility::Win32, 0x57),
+            HResult::E_INVALIDARG
+        );
+        assert_eq!(HResult::new(Severity::Success, Facility::Null, 0), HResult::S_OK);
+        let hr = HResult::new(Severity::Error, Facility::from_u16(0x123), 0xabcd);
+        assert_eq!(hr.facility(), Facility::from_u16(0x123));
+        assert_eq!(hr.code_part(), 0xabcd);
+    }
+
+    #[test]
+    #[cfg(feature = "hresult-table")]
+    fn table_test() {
+        assert_eq!(HResult::E_ACCESSDENIED.symbol(), Some("E_ACCESSDENIED"));
+        assert_eq!(HResult(0x887A0005u32 as HRESULT).symbol(), Some("DXGI_ERROR_DEVICE_REMOVED"));
+        assert_eq!(HResult(0x80070000u32 as HRESULT).symbol(), None);
+        assert_eq!(
+            HResult::E_ACCESSDENIED.to_string(),
+            "E_ACCESSDENIED (0x80070005): Access is denied."
+        );
+        assert_eq!(HResult::S_FALSE.to_string(), "S_FALSE (0x00000001)");
+    }
+
+    #[test]
+    fn hex_test() {
+        let hr = HResult::E_ACCESSDENIED;
+        assert_eq!(format!("{:#x}", hr), "0x80070005");
+        assert_eq!(format!("{:08X}", HResult::S_FALSE), "00000001");
+        assert_eq!(format!("{:#010x}", HResult::S_OK), "0x00000000");
+    }
+
+    #[test]
+    fn from_str_test() {
+        let hr = HResult::E_ACCESSDENIED;
+        assert_eq!("0x80070005".parse(), Ok(hr));
+        assert_eq!("0X80070005".parse(), Ok(hr));
+        assert_eq!("-2147024891".parse(), Ok(hr));
+        assert_eq!("2147942405".parse(), Ok(hr));
+        assert_eq!(" E_ACCESSDENIED ".parse(), Ok(hr));
+        assert_eq!("1".parse(), Ok(HResult::S_FALSE));
+        assert!("0x100000000".parse::<HResult>().is_err());
+        assert!("4294967296".parse::<HResult>().is_err());
+        assert!("E_UNKNOWN_SYMBOL".parse::<HResult>().is_err());
+        assert!("".parse::<HResult>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "hresult-table")]
+    fn from_str_table_test() {
+        assert_eq!(
+            "DXGI_ERROR_DEVICE_REMOVED".parse(),
+            Ok(HResult(0x887A0005u32 as HRESULT))
+        );
+    }
+
+    #[test]
+    fn facility_test() {
+        assert_eq!(Facility::from(2170), Facility::Dxgi);
+        assert_eq!(u16::from(Facility::Itf), 4);
+        assert_eq!(Facility::from_u16(7), Facility::Win32);
+        match Facility::from_u16(5) {
+            Facility::Other(other) => assert_eq!(other.value(), 5),
+            f => panic!("{:?}", f),
+        }
+        assert_eq!(Facility::from_u16(5).to_u16(), 5);
+        assert_eq!(Facility::from_u16(0x1fff).to_u16(), 0x1fff);
+    }
+
+    #[test]
+    #[should_panic]
+    fn facility_range_test() {
+        Facility::from_u16(0x2000);
+    }
+}
@@ -0,0 +1,209 @@
+//! COM identity of objects.
+
+use crate::sys::*;
+use crate::{ComPtr, ComRef, HResult};
+use std::hash::{Hash, Hasher};
+
+impl<'a, T: Interface> ComRef<'a, T> {
+    /// Returns the `IUnknown` of the object.
+    ///
+    /// The `IUnknown` queried from any interface of the same object has the same pointer.
+    /// Returns the HRESULT when `QueryInterface` fails.
+    #[inline]
+    pub fn identity(&self) -> Result<ComPtr<IUnknown>, HResult> {
+        self.query_interface::<IUnknown>()
+    }
+
+    /// Returns `true` when `self` and `other` are interfaces of the same object.
+    ///
+    /// When `QueryInterface` for `IUnknown` fails, only the pointers of the interfaces are compared.
+    pub fn is_same_object<U: Interface>(&self, other: ComRef<U>) -> bool {
+        if self.as_ptr() as *mut c_void == other.as_ptr() as *mut c_void {
+            return true;
+        }
+        match (self.identity(), other.identity()) {
+            (Ok(a), Ok(b)) => a.as_ptr() == b.as_ptr(),
+            _ => false,
+        }
+    }
+}
+
+impl<T: Interface> ComPtr<T> {
+    /// Returns the `IUnknown` of the object.
+    ///
+    /// The `IUnknown` queried from any interface of the same object has the same pointer.
+    /// Returns the HRESULT when `QueryInterface` fails.
+    #[inline]
+    pub fn identity(&self) -> Result<ComPtr<IUnknown>, HResult> {
+        self.as_ref_ptr().identity()
+    }
+
+    /// Returns `true` when `self` and `other` are interfaces of the same object.
+    ///
+    /// `==` compares the pointers of the interfaces, which can differ for the same object.
+    /// When `QueryInterface` for `IUnknown` fails, only the pointers of the interfaces are compared.
+    #[inline]
+    pub fn is_same_object<U: Interface>(&self, other: &ComPtr<U>) -> bool {
+        self.as_ref_ptr().is_same_object(other.as_ref_ptr())
+    }
+}
+
+/// Hashes the pointer of the interface like `==`.
+impl<T: Interface> Hash for ComPtr<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ptr().hash(state);
+    }
+}
+
+/// Hashes the pointer of the interface like `==`.
+impl<'a, T: Interface> Hash for ComRef<'a, T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ptr().hash(state);
+    }
+}
+
+/// A key that compares and hashes the COM identity of objects.
+///
+/// The keys from any interfaces of the same object are equal.
+/// `IdentityKey` has a reference of the object, so the address is not reused while the key is alive.
+///
+/// ```
+/// # use com_ptr::{ComPtr, IdentityKey};
+/// # use com_ptr::sys::IUnknown;
+/// # use std::collections::HashMap;
+/// fn count(objects: &[ComPtr<IUnknown>]) -> HashMap<IdentityKey, usize> {
+///     let mut map = HashMap::new();
+///     for obj in objects {
+///         *map.entry(IdentityKey::new(obj)).or_insert(0) += 1;
+///     }
+///     map
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct IdentityKey(ComPtr<IUnknown>);
+
+impl IdentityKey {
+    /// Creates a key from the identity of the object.
+    ///
+    /// When `QueryInterface` for `IUnknown` fails, the key has the pointer of the interface.
+    pub fn new<T: Interface>(ptr: &ComPtr<T>) -> IdentityKey {
+        IdentityKey(ptr.identity().unwrap_or_else(|_| unsafe {
+            ComPtr::from_raw(ptr.clone().into_raw() as *mut IUnknown)
+        }))
+    }
+
+    /// Returns the `IUnknown` of the object.
+    #[inline]
+    pub fn as_unknown(&self) -> &ComPtr<IUnknown> {
+        &self.0
+    }
+
+    /// Returns the `IUnknown` of the object.
+    #[inline]
+    pub fn into_inner(self) -> ComPtr<IUnknown> {
+        self.0
+    }
+}
+
+impl<T: Interface> From<&ComPtr<T>> for IdentityKey {
+    #[inline]
+    fn from(src: &ComPtr<T>) -> IdentityKey {
+        IdentityKey::new(src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::null_object;
+    use crate::{com_class, com_impl};
+    use std::collections::{HashMap, HashSet};
+
+    com_interface! {
+        #[uuid(0x7a3e5f20, 0x1c8b, 0x4d2e, 0x96, 0x4f, 0x3b, 0x81, 0xe2, 0x0c, 0x5d, 0x71)]
+        interface IFoo(IFooVtbl): IUnknown(IUnknownVtbl) {
+            fn Foo() -> u32,
+        }
+    }
+
+    com_interface! {
+        #[uuid(0x7a3e5f20, 0x1c8b, 0x4d2e, 0x96, 0x4f, 0x3b, 0x81, 0xe2, 0x0c, 0x5d, 0x72)]
+        interface IBar(IBarVtbl): IUnknown(IUnknownVtbl) {
+            fn Bar() -> u32,
+        }
+    }
+
+    #[com_class(IFoo, IBar)]
+    struct Object;
+
+    #[com_impl(IFoo)]
+    #[allow(non_snake_case)]
+    impl Object {
+        fn Foo(&self) -> u32 {
+            1
+        }
+    }
+
+    #[com_impl(IBar)]
+    #[allow(non_snake_case)]
+    impl Object {
+        fn Bar(&self) -> u32 {
+            2
+        }
+    }
+
+    #[test]
+    fn identity_test() {
+        let foo: ComPtr<IFoo> = Object.into_com_ptr();
+        let bar = foo.query_interface::<IBar>().unwrap();
+        assert_ne!(foo.as_ptr() as usize, bar.as_ptr() as usize);
+        assert_eq!(unsafe { (foo.Foo(), bar.Bar()) }, (1, 2));
+        assert!(foo.identity().unwrap() == bar.identity().unwrap());
+        assert!(foo.is_same_object(&bar));
+        assert!(bar.is_same_object(&foo));
+        assert!(foo.as_ref_ptr().is_same_object(bar.as_ref_ptr()));
+        let other: ComPtr<IFoo> = Object.into_com_ptr();
+        assert!(!other.is_same_object(&bar));
+    }
+
+    #[test]
+    fn no_identity_test() {
+        let mut obj = null_object();
+        let mut other = null_object();
+        let p = unsafe { ComPtr::from_raw(&mut obj as *mut IUnknown) };
+        let q = unsafe { ComPtr::from_raw(&mut other as *mut IUnknown) };
+        assert_eq!(p.identity().unwrap_err(), HResult::E_POINTER);
+        assert!(p.is_same_object(&p.clone()));
+        assert!(!p.is_same_object(&q));
+        assert!(IdentityKey::new(&p) == IdentityKey::new(&p));
+        assert!(IdentityKey::new(&p) != IdentityKey::new(&q));
+        assert_eq!(IdentityKey::new(&p).as_unknown().as_ptr(), p.as_ptr());
+    }
+
+    #[test]
+    fn key_test() {
+        let foo: ComPtr<IFoo> = Object.into_com_ptr();
+        let bar = foo.query_interface::<IBar>().unwrap();
+        let other: ComPtr<IFoo> = Object.into_com_ptr();
+        let mut map = HashMap::new();
+        map.insert(IdentityKey::new(&foo), 1);
+        map.insert(IdentityKey::from(&other), 2);
+        *map.get_mut(&IdentityKey::new(&bar)).unwrap() += 10;
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&IdentityKey::new(&foo)], 11);
+        assert!(IdentityKey::new(&bar).as_unknown().is_same_object(&foo));
+    }
+
+    #[test]
+    fn hash_test() {
+        let foo: ComPtr<IFoo> = Object.into_com_ptr();
+        let mut set = HashSet::new();
+        set.insert(foo.clone());
+        set.insert(foo.clone());
+        set.insert(Object.into_com_ptr());
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&foo));
+    }
+}
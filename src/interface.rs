@@ -0,0 +1,402 @@
+//! Declaring COM interfaces.
+
+use crate::sys::{IUnknown, Interface, IsEqualGUID, GUID};
+
+/// A COM interface whose IID is known at compile time.
+///
+/// [`com_interface!`](crate::com_interface!) implements this trait. Interfaces from other binding
+/// generators can implement this trait to be used with `ComPtr`. On platforms other than Windows,
+/// `Interface` is implemented for all `ComInterface` types, so winapi is not needed. On Windows,
+/// `Interface` is `winapi::Interface`, which such interfaces implement as well. This crate
+/// implements this trait for some winapi interfaces as described in [`sys`](crate::sys).
+///
+/// ```
+/// use com_ptr::{com_interface, ComInterface};
+/// use com_ptr::sys::{IUnknown, IUnknownVtbl, IsEqualGUID, GUID};
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+///     interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+///         fn GetValue() -> u32,
+///     }
+/// }
+///
+/// static IIDS: [GUID; 2] = [IUnknown::IID, IValue::IID];
+/// assert!(IValue::is_iid(&IIDS[1]));
+/// assert!(IsEqualGUID(&IIDS[0], &IUnknown::IID));
+/// ```
+///
+/// ## Safety
+/// `Self` must have the pointer to `Vtable` as the first field, and `IID` must be the IID of the interface.
+pub unsafe trait ComInterface: Sized {
+    /// The vtable of the interface.
+    type Vtable;
+
+    /// The IID of the interface.
+    const IID: GUID;
+
+    /// Returns the vtable.
+    #[inline]
+    fn vtable(&self) -> &Self::Vtable {
+        unsafe { &**(self as *const Self as *const *const Self::Vtable) }
+    }
+
+    /// Returns whether `iid` is the IID of the interface.
+    #[inline]
+    fn is_iid(iid: &GUID) -> bool {
+        IsEqualGUID(iid, &Self::IID)
+    }
+}
+
+/// A marker for interfaces that derive from `Base` directly or indirectly.
+///
+/// A pointer to `Self` is a valid pointer to `Base` because the vtable of `Base` is the prefix of
+/// the vtable of `Self`. `ComPtr<Self>` is converted to `ComPtr<Base>` by [`ComPtr::upcast`](crate::ComPtr::upcast)
+/// without `QueryInterface`.
+///
+/// [`com_interface!`](crate::com_interface!) implements this trait for the base interface and its
+/// ancestors, and [`inherits!`](crate::inherits!) implements it for interfaces declared elsewhere.
+///
+/// ```
+/// use com_ptr::{com_interface, ComPtr};
+/// use com_ptr::sys::{IUnknown, IUnknownVtbl};
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+///     interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+///         fn GetValue() -> u32,
+///     }
+/// }
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x53)]
+///     interface IValue2(IValue2Vtbl): IValue(IValueVtbl) {
+///         fn SetValue(value: u32) -> u32,
+///     }
+/// }
+///
+/// fn upcast(p: ComPtr<IValue2>) -> (ComPtr<IValue>, ComPtr<IUnknown>) {
+///     (p.clone().upcast(), p.into())
+/// }
+/// ```
+///
+/// ```compile_fail
+/// # use com_ptr::{com_interface, ComPtr};
+/// # use com_ptr::sys::{IUnknown, IUnknownVtbl};
+/// # com_interface! {
+/// #     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+/// #     interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+/// #         fn GetValue() -> u32,
+/// #     }
+/// # }
+/// fn downcast(p: ComPtr<IUnknown>) -> ComPtr<IValue> {
+///     p.upcast()
+/// }
+/// ```
+///
+/// ## Safety
+/// `Self` must derive from `Base`.
+pub unsafe trait Inherits<Base: Interface>: Interface {}
+
+/// Implements [`Inherits`] for an interface declared without [`com_interface!`](crate::com_interface!).
+///
+/// `inherits!(Derived: Base)` implements `Inherits<Base>` for `Derived` and `Inherits<T>`
+/// for every `T` that `Base` inherits. The base interface must be `IUnknown`, a winapi interface
+/// of [`sys`](crate::sys) or an interface declared by `inherits!` or `com_interface!`.
+/// Because of the orphan rules, `Derived` must be declared in the crate that uses this macro.
+///
+/// ## Safety
+/// `Derived` must derive from `Base`.
+#[macro_export]
+macro_rules! inherits {
+    ($($derived:ty: $base:ty),* $(,)?) => {
+        $(
+            unsafe impl<T: $crate::sys::Interface> $crate::Inherits<T> for $derived
+            where
+                $base: $crate::IsOrInherits<T>,
+            {
+            }
+
+            unsafe impl $crate::IsOrInherits<$derived> for $derived {}
+
+            unsafe impl<T: $crate::sys::Interface> $crate::IsOrInherits<T> for $derived
+            where
+                $derived: $crate::Inherits<T>,
+            {
+            }
+        )*
+    };
+}
+
+/// `Self` is `Base` or inherits `Base`.
+///
+/// `Inherits` excludes `Self` so that `From<ComPtr<T>>` does not conflict with `From<T> for T`.
+/// This trait makes the hierarchy transitive.
+#[doc(hidden)]
+pub unsafe trait IsOrInherits<Base: Interface>: Interface {}
+
+unsafe impl IsOrInherits<IUnknown> for IUnknown {}
+
+/// Declares a COM interface.
+///
+/// This macro generates the interface, the `#[repr(C)]` vtable, the methods that call the vtable
+/// and the implementation of [`ComInterface`]. On Windows, `Interface` is also implemented.
+/// The interface derefs to the base interface and implements [`Inherits`] for the base interface
+/// and its ancestors.
+/// The syntax is the same as `RIDL!` of winapi.
+///
+/// The base interface must be `IUnknown`, a winapi interface of [`sys`](crate::sys) or an
+/// interface declared by `com_interface!` or [`inherits!`](crate::inherits!). Otherwise the
+/// hierarchy is unknown and the declaration fails to compile.
+///
+/// ```compile_fail
+/// use com_ptr::{com_interface, ComInterface};
+/// use com_ptr::sys::{IUnknown, IUnknownVtbl, GUID};
+///
+/// #[repr(C)]
+/// pub struct IForeign {
+///     lpVtbl: *const IUnknownVtbl,
+/// }
+///
+/// unsafe impl ComInterface for IForeign {
+///     type Vtable = IUnknownVtbl;
+///     const IID: GUID = GUID {
+///         Data1: 0x6b0d26a1,
+///         Data2: 0x2a33,
+///         Data3: 0x4e5c,
+///         Data4: [0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52],
+///     };
+/// }
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x53)]
+///     interface IValue(IValueVtbl): IForeign(IUnknownVtbl) {
+///         fn GetValue() -> u32,
+///     }
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use com_ptr::com_interface;
+/// use com_ptr::sys::{IUnknown, IUnknownVtbl, HRESULT};
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+///     interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+///         fn GetValue(
+///             value: *mut u32,
+///         ) -> HRESULT,
+///         fn SetValue(
+///             value: u32,
+///         ) -> HRESULT,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! com_interface {
+    (
+        #[uuid($l:expr, $w1:expr, $w2:expr,
+            $b1:expr, $b2:expr, $b3:expr, $b4:expr, $b5:expr, $b6:expr, $b7:expr, $b8:expr)]
+        $(#[$attr:meta])*
+        interface $interface:ident($vtbl:ident): $pinterface:ident($pvtbl:ident) {
+            $(
+                $(#[$fattr:meta])*
+                fn $method:ident($($p:ident: $t:ty),* $(,)?) -> $rtr:ty,
+            )*
+        }
+    ) => {
+        #[repr(C)]
+        #[allow(non_snake_case)]
+        pub struct $vtbl {
+            pub parent: $pvtbl,
+            $(pub $method: unsafe extern "system" fn(This: *mut $interface, $($p: $t,)*) -> $rtr,)*
+        }
+
+        #[repr(C)]
+        #[allow(non_snake_case)]
+        $(#[$attr])*
+        pub struct $interface {
+            pub lpVtbl: *const $vtbl,
+        }
+
+        #[allow(non_snake_case, clippy::missing_safety_doc, clippy::too_many_arguments)]
+        impl $interface {
+            $(
+                $(#[$fattr])*
+                #[inline]
+                pub unsafe fn $method(&self, $($p: $t,)*) -> $rtr {
+                    ((*self.lpVtbl).$method)(self as *const _ as *mut _, $($p,)*)
+                }
+            )*
+        }
+
+        impl ::std::ops::Deref for $interface {
+            type Target = $pinterface;
+
+            #[inline]
+            fn deref(&self) -> &$pinterface {
+                unsafe { &*(self as *const $interface as *const $pinterface) }
+            }
+        }
+
+        unsafe impl $crate::ComInterface for $interface {
+            type Vtable = $vtbl;
+
+            const IID: $crate::sys::GUID = $crate::sys::GUID {
+                Data1: $l,
+                Data2: $w1,
+                Data3: $w2,
+                Data4: [$b1, $b2, $b3, $b4, $b5, $b6, $b7, $b8],
+            };
+        }
+
+        #[cfg(windows)]
+        unsafe impl $crate::sys::Interface for $interface {
+            #[inline]
+            fn uuidof() -> $crate::sys::GUID {
+                <$interface as $crate::ComInterface>::IID
+            }
+        }
+
+        $crate::inherits!($interface: $pinterface);
+
+        const _: fn() = || {
+            fn base<T: $crate::IsOrInherits<$crate::sys::IUnknown>>() {}
+            base::<$pinterface>();
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::count;
+    use crate::sys::*;
+    use crate::{com_class, com_impl, ComPtr};
+
+    com_interface! {
+        #[uuid(0x3c1e6a70, 0x5b2d, 0x4a8f, 0x9e, 0x11, 0x27, 0x4d, 0x60, 0xb3, 0xc8, 0x01)]
+        interface ICounter(ICounterVtbl): IUnknown(IUnknownVtbl) {
+            fn Increment() -> u32,
+            fn Get(value: *mut u32) -> HRESULT,
+        }
+    }
+
+    com_interface! {
+        #[uuid(0x3c1e6a70, 0x5b2d, 0x4a8f, 0x9e, 0x11, 0x27, 0x4d, 0x60, 0xb3, 0xc8, 0x02)]
+        /// A counter that can be reset.
+        interface IResettableCounter(IResettableCounterVtbl): ICounter(ICounterVtbl) {
+            fn Reset(
+                value: u32,
+            ) -> HRESULT,
+        }
+    }
+
+    #[com_class(IResettableCounter)]
+    struct Counter(std::cell::Cell<u32>);
+
+    #[com_impl(ICounter)]
+    #[allow(non_snake_case)]
+    impl Counter {
+        fn Increment(&self) -> u32 {
+            self.0.set(self.0.get() + 1);
+            self.0.get()
+        }
+
+        unsafe fn Get(&self, value: *mut u32) -> HRESULT {
+            *value = self.0.get();
+            S_OK
+        }
+    }
+
+    #[com_impl(IResettableCounter: ICounter)]
+    #[allow(non_snake_case)]
+    impl Counter {
+        fn Reset(&self, value: u32) -> HRESULT {
+            self.0.set(value);
+            S_OK
+        }
+    }
+
+    #[test]
+    fn uuidof_test() {
+        let iid = ICounter::uuidof();
+        assert_eq!(iid.Data1, 0x3c1e6a70);
+        assert_eq!(iid.Data2, 0x5b2d);
+        assert_eq!(iid.Data3, 0x4a8f);
+        assert_eq!(iid.Data4, [0x9e, 0x11, 0x27, 0x4d, 0x60, 0xb3, 0xc8, 0x01]);
+    }
+
+    #[test]
+    fn const_iid_test() {
+        const IIDS: [GUID; 2] = [ICounter::IID, IResettableCounter::IID];
+        assert!(IsEqualGUID(&IIDS[0], &ICounter::uuidof()));
+        assert!(IResettableCounter::is_iid(&IIDS[1]));
+        assert!(!ICounter::is_iid(&IIDS[1]));
+        assert!(IUnknown::is_iid(&IUnknown::uuidof()));
+        let p: ComPtr<IResettableCounter> = Counter(Default::default()).into_com_ptr();
+        let vtbl: &IResettableCounterVtbl = p.vtable();
+        assert!(std::ptr::eq(vtbl, p.lpVtbl));
+    }
+
+    /// An interface from another binding generator.
+    #[repr(C)]
+    struct IForeignCounter {
+        vtbl: *const ICounterVtbl,
+    }
+
+    unsafe impl ComInterface for IForeignCounter {
+        type Vtable = ICounterVtbl;
+        const IID: GUID = ICounter::IID;
+    }
+
+    #[cfg(windows)]
+    unsafe impl Interface for IForeignCounter {
+        fn uuidof() -> GUID {
+            ICounter::IID
+        }
+    }
+
+    #[test]
+    fn foreign_test() {
+        let p: ComPtr<IResettableCounter> = Counter(Default::default()).into_com_ptr();
+        let q = p.query_interface::<IForeignCounter>().unwrap();
+        assert_eq!(unsafe { (q.vtable().Increment)(q.as_ptr() as *mut ICounter) }, 1);
+    }
+
+    #[test]
+    fn upcast_test() {
+        let p: ComPtr<IResettableCounter> = Counter(Default::default()).into_com_ptr();
+        let counter: ComPtr<ICounter> = p.clone().upcast();
+        assert_eq!(counter.as_ptr() as usize, p.as_ptr() as usize);
+        assert_eq!(count(&p), 2);
+        assert_eq!(unsafe { counter.Increment() }, 1);
+        let unknown = ComPtr::<IUnknown>::from(p.clone());
+        assert_eq!(unknown.as_ptr() as usize, p.as_ptr() as usize);
+        assert_eq!(count(&p), 3);
+        let unknown2: ComPtr<IUnknown> = counter.into();
+        assert!(unknown == unknown2);
+        assert_eq!(count(&p), 3);
+        drop(unknown);
+        drop(unknown2);
+        assert_eq!(count(&p), 1);
+    }
+
+    #[test]
+    fn call_test() {
+        let p: ComPtr<IResettableCounter> = Counter(Default::default()).into_com_ptr();
+        unsafe {
+            assert_eq!(p.Increment(), 1);
+            assert_eq!(p.Reset(10), S_OK);
+            let counter = p.query_interface::<ICounter>().unwrap();
+            assert_eq!(counter.Increment(), 11);
+            let mut value = 0;
+            assert_eq!(counter.Get(&mut value), S_OK);
+            assert_eq!(value, 11);
+            assert_eq!(counter.AddRef(), 3);
+            assert_eq!(counter.Release(), 2);
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Conversions with the `windows` and `windows-sys` crates.
+//!
+//! With the `windows` feature, `ComPtr`, `HResult` and `ComError` are converted to and from
+//! the types of `windows::core`. With the `windows-sys` feature, GUIDs are converted to and from
+//! `windows_sys::core::GUID`.
+//!
+//! The interface types of the `windows` crate cannot be held by `ComPtr` directly because they
+//! are smart pointers that own the reference themselves, while `ComPtr<T>` points to the `T`
+//! whose first field is the vtable pointer. They are converted to and from a `ComPtr` of the
+//! interface with the same IID by [`ComPtr::into_windows`](crate::ComPtr::into_windows) and
+//! [`ComPtr::from_windows`](crate::ComPtr::from_windows).
+
+#[cfg(feature = "windows")]
+pub use self::windows::*;
+#[cfg(feature = "windows-sys")]
+pub use self::windows_sys::*;
+
+#[cfg(feature = "windows")]
+mod windows {
+    use crate::sys::*;
+    use crate::{ComError, ComPtr, HResult};
+
+    /// Converts a `GUID` to `windows::core::GUID`.
+    #[inline]
+    pub fn to_windows_guid(guid: &GUID) -> windows_core::GUID {
+        windows_core::GUID {
+            data1: guid.Data1,
+            data2: guid.Data2,
+            data3: guid.Data3,
+            data4: guid.Data4,
+        }
+    }
+
+    /// Converts a `windows::core::GUID` to `GUID`.
+    #[inline]
+    pub fn from_windows_guid(guid: &windows_core::GUID) -> GUID {
+        GUID {
+            Data1: guid.data1,
+            Data2: guid.data2,
+            Data3: guid.data3,
+            Data4: guid.data4,
+        }
+    }
+
+    fn has_iid<T: Interface, I: windows_core::Interface>() -> bool {
+        I::UNKNOWN && to_windows_guid(&T::uuidof()) == I::IID
+    }
+
+    impl<T: Interface> ComPtr<T> {
+        /// Converts into an interface of the `windows` crate.
+        ///
+        /// When `I` has the same IID as `T`, the reference is transferred without `AddRef` and
+        /// `Release`. Otherwise, `I` is queried from the object, and `E_POINTER` is returned when
+        /// the object returns a null interface.
+        pub fn into_windows<I: windows_core::Interface>(self) -> Result<I, HResult> {
+            if has_iid::<T, I>() {
+                return Ok(unsafe { I::from_raw(self.into_raw() as *mut c_void) });
+            }
+            if !I::UNKNOWN {
+                return Err(HResult(E_NOINTERFACE));
+            }
+            // The interface of `I` is held as `IUnknown` until it is converted.
+            let p = unsafe {
+                ComPtr::<IUnknown>::from_iid_out(|_, p| {
+                    self.as_unknown()
+                        .QueryInterface(&from_windows_guid(&I::IID), p)
+                })?
+            };
+            Ok(unsafe { I::from_raw(p.into_raw() as *mut c_void) })
+        }
+
+        /// Creates a `ComPtr` from an interface of the `windows` crate.
+        ///
+        /// When `I` has the same IID as `T`, the reference is transferred without `AddRef` and
+        /// `Release`. Otherwise, `T` is queried from the object.
+        pub fn from_windows<I: windows_core::Interface>(src: I) -> Result<ComPtr<T>, HResult> {
+            if has_iid::<T, I>() {
+                return Ok(unsafe { ComPtr::from_raw(src.into_raw() as *mut T) });
+            }
+            if !I::UNKNOWN {
+                return Err(HResult(E_NOINTERFACE));
+            }
+            let unknown = unsafe { ComPtr::from_raw(src.into_raw() as *mut IUnknown) };
+            unknown.query_interface::<T>()
+        }
+
+        /// Returns a reference as an interface of the `windows` crate without `AddRef`.
+        ///
+        /// Returns `None` when `I` does not have the same IID as `T`.
+        #[inline]
+        pub fn as_windows<I: windows_core::Interface>(&self) -> Option<&I> {
+            if has_iid::<T, I>() {
+                unsafe { I::from_raw_borrowed(&*(&self.p as *const _ as *const *mut c_void)) }
+            } else {
+                None
+            }
+        }
+    }
+
+    /// The reference is transferred because both are `IUnknown`.
+    impl From<ComPtr<IUnknown>> for windows_core::IUnknown {
+        #[inline]
+        fn from(src: ComPtr<IUnknown>) -> windows_core::IUnknown {
+            unsafe {
+                <windows_core::IUnknown as windows_core::Interface>::from_raw(
+                    src.into_raw() as *mut c_void
+                )
+            }
+        }
+    }
+
+    /// The reference is transferred because both are `IUnknown`.
+    impl From<windows_core::IUnknown> for ComPtr<IUnknown> {
+        #[inline]
+        fn from(src: windows_core::IUnknown) -> ComPtr<IUnknown> {
+            unsafe { ComPtr::from_raw(windows_core::Interface::into_raw(src) as *mut IUnknown) }
+        }
+    }
+
+    impl From<HResult> for windows_core::HRESULT {
+        #[inline]
+        fn from(src: HResult) -> windows_core::HRESULT {
+            windows_core::HRESULT(src.0)
+        }
+    }
+
+    impl From<windows_core::HRESULT> for HResult {
+        #[inline]
+        fn from(src: windows_core::HRESULT) -> HResult {
+            HResult(src.0)
+        }
+    }
+
+    /// The error information of the current thread is not taken.
+    impl From<HResult> for windows_core::Error {
+        #[inline]
+        fn from(src: HResult) -> windows_core::Error {
+            windows_core::Error::from_hresult(src.into())
+        }
+    }
+
+    impl From<windows_core::Error> for HResult {
+        #[inline]
+        fn from(src: windows_core::Error) -> HResult {
+            src.code().into()
+        }
+    }
+
+    /// The message of the error becomes the description.
+    impl From<windows_core::Error> for ComError {
+        fn from(src: windows_core::Error) -> ComError {
+            let e = ComError::new(src.code().into());
+            let message = src.message();
+            let message = message.trim_end();
+            if message.is_empty() {
+                e
+            } else {
+                e.with_description(message)
+            }
+        }
+    }
+
+    /// The description becomes the message of the error.
+    impl From<ComError> for windows_core::Error {
+        fn from(src: ComError) -> windows_core::Error {
+            windows_core::Error::new(src.hresult().into(), src.description().unwrap_or(""))
+        }
+    }
+}
+
+#[cfg(feature = "windows-sys")]
+mod windows_sys {
+    use crate::sys::GUID;
+
+    /// Converts a `GUID` to `windows_sys::core::GUID`.
+    #[inline]
+    pub fn to_sys_guid(guid: &GUID) -> ::windows_sys::core::GUID {
+        ::windows_sys::core::GUID {
+            data1: guid.Data1,
+            data2: guid.Data2,
+            data3: guid.Data3,
+            data4: guid.Data4,
+        }
+    }
+
+    /// Converts a `windows_sys::core::GUID` to `GUID`.
+    #[inline]
+    pub fn from_sys_guid(guid: &::windows_sys::core::GUID) -> GUID {
+        GUID {
+            Data1: guid.data1,
+            Data2: guid.data2,
+            Data3: guid.data3,
+            Data4: guid.data4,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use crate::sys::*;
+
+    #[cfg(feature = "windows")]
+    mod windows {
+        use super::*;
+        use crate::fixture::{count, null_object, IValue, Value};
+        use crate::{ComError, ComPtr, HResult};
+        use windows_core::Interface as _;
+
+        #[windows_core::interface("5e4b9a12-7c3d-4f60-a18e-2d47b096e315")]
+        unsafe trait IWinValue: windows_core::IUnknown {
+            fn Get(&self) -> u32;
+        }
+
+        #[test]
+        fn same_iid_test() {
+            let p = Value::new(3).into_com_ptr();
+            let q = p.clone();
+            assert_eq!(count(&p), 2);
+            let w = q.into_windows::<IWinValue>().unwrap();
+            assert_eq!(count(&p), 2);
+            assert_eq!(unsafe { w.Get() }, 3);
+            assert_eq!(w.as_raw(), p.as_ptr() as *mut c_void);
+            let q = ComPtr::<IValue>::from_windows(w).unwrap();
+            assert_eq!(count(&p), 2);
+            assert!(p == q);
+            let r = p.as_windows::<IWinValue>().unwrap();
+            assert_eq!(unsafe { r.Get() }, 3);
+            assert_eq!(count(&p), 2);
+            assert!(p.as_windows::<windows_core::IUnknown>().is_none());
+        }
+
+        #[test]
+        fn query_test() {
+            let p = Value::new(3).into_com_ptr();
+            let unknown = windows_core::IUnknown::from(p.query_interface::<IUnknown>().unwrap());
+            assert_eq!(count(&p), 2);
+            let q = ComPtr::<IValue>::from_windows(unknown).unwrap();
+            assert_eq!(count(&p), 2);
+            assert!(p == q);
+            drop(q);
+            let unknown = windows_core::IUnknown::from(p.query_interface::<IUnknown>().unwrap());
+            let q = ComPtr::<IUnknown>::from(unknown);
+            assert_eq!(count(&p), 2);
+            assert_eq!(unsafe { p.Get() }, 3);
+            drop(q);
+            assert_eq!(count(&p), 1);
+        }
+
+        #[test]
+        fn null_test() {
+            let mut obj = null_object();
+            let p = unsafe { ComPtr::from_raw(&mut obj as *mut IUnknown) };
+            assert_eq!(
+                p.into_windows::<IWinValue>().unwrap_err(),
+                HResult::E_POINTER
+            );
+        }
+
+        #[test]
+        fn hresult_test() {
+            let hr = windows_core::HRESULT::from(HResult::E_FAIL);
+            assert_eq!(hr.0, HResult::E_FAIL.0);
+            assert_eq!(HResult::from(hr), HResult::E_FAIL);
+            let e = windows_core::Error::from(HResult::E_ACCESSDENIED);
+            assert_eq!(HResult::from(e), HResult::E_ACCESSDENIED);
+            let e = windows_core::Error::from(ComError::new(HResult::E_FAIL));
+            assert_eq!(ComError::from(e).hresult(), HResult::E_FAIL);
+        }
+    }
+
+    #[cfg(feature = "windows-sys")]
+    #[test]
+    fn sys_guid_test() {
+        use super::{from_sys_guid, to_sys_guid};
+
+        let iid = IUnknown::uuidof();
+        let guid = to_sys_guid(&iid);
+        assert_eq!(guid.data1, ::windows_sys::core::IID_IUnknown.data1);
+        assert_eq!(guid.data4, ::windows_sys::core::IID_IUnknown.data4);
+        assert!(IsEqualGUID(&from_sys_guid(&guid), &iid));
+    }
+}
@@ -0,0 +1,158 @@
+//! Conversions between HResult and `std::io::Error`.
+
+use crate::{HResult, Win32Error};
+use std::io::{self, ErrorKind};
+
+impl HResult {
+    /// Returns the `io::ErrorKind` that corresponds to the HRESULT.
+    ///
+    /// Returns `ErrorKind::Other` when there is no corresponding kind. `E_ABORT` is also `Other`
+    /// because `ErrorKind::Interrupted` is retried by the loops of `std::io` such as `read_exact`.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            HResult::E_ACCESSDENIED | HResult::STG_E_ACCESSDENIED => ErrorKind::PermissionDenied,
+            HResult::STG_E_FILENOTFOUND | HResult::STG_E_PATHNOTFOUND => ErrorKind::NotFound,
+            HResult::STG_E_FILEALREADYEXISTS => ErrorKind::AlreadyExists,
+            HResult::E_INVALIDARG | HResult::E_POINTER | HResult::STG_E_INVALIDPARAMETER => {
+                ErrorKind::InvalidInput
+            }
+            HResult::E_OUTOFMEMORY | HResult::STG_E_INSUFFICIENTMEMORY => ErrorKind::OutOfMemory,
+            HResult::E_NOTIMPL => ErrorKind::Unsupported,
+            HResult::E_PENDING => ErrorKind::WouldBlock,
+            _ => {
+                match Win32Error::from_hresult(*self) {
+                    Some(Win32Error::ERROR_FILE_NOT_FOUND)
+                    | Some(Win32Error::ERROR_PATH_NOT_FOUND) => ErrorKind::NotFound,
+                    Some(Win32Error::ERROR_ALREADY_EXISTS)
+                    | Some(Win32Error::ERROR_FILE_EXISTS) => ErrorKind::AlreadyExists,
+                    Some(Win32Error::ERROR_NOT_ENOUGH_MEMORY) => ErrorKind::OutOfMemory,
+                    Some(Win32Error::ERROR_INVALID_DATA) => ErrorKind::InvalidData,
+                    Some(Win32Error::ERROR_HANDLE_EOF) => ErrorKind::UnexpectedEof,
+                    Some(Win32Error::ERROR_NOT_SUPPORTED) => ErrorKind::Unsupported,
+                    Some(Win32Error::ERROR_BROKEN_PIPE) => ErrorKind::BrokenPipe,
+                    Some(Win32Error::ERROR_TIMEOUT) => ErrorKind::TimedOut,
+                    _ => ErrorKind::Other,
+                }
+            }
+        }
+    }
+}
+
+/// The `io::Error` has the kind of [`HResult::kind`] and the `HResult` as the inner error.
+///
+/// The `HResult` can be recovered by `HResult::from` or `io::Error::get_ref`.
+impl From<HResult> for io::Error {
+    #[inline]
+    fn from(src: HResult) -> io::Error {
+        io::Error::new(src.kind(), src)
+    }
+}
+
+/// Returns the inner `HResult` when `src` was converted from `HResult`.
+/// Otherwise, the OS error code is converted by `HRESULT_FROM_WIN32` on Windows,
+/// and the kind of the error is converted on other platforms.
+impl From<io::Error> for HResult {
+    fn from(src: io::Error) -> HResult {
+        if let Some(hr) = src.get_ref().and_then(|e| e.downcast_ref::<HResult>()) {
+            return *hr;
+        }
+        #[cfg(windows)]
+        {
+            if let Some(code) = src.raw_os_error() {
+                return Win32Error(code as u32).to_hresult();
+            }
+        }
+        match src.kind() {
+            ErrorKind::NotFound => Win32Error::ERROR_FILE_NOT_FOUND.to_hresult(),
+            ErrorKind::PermissionDenied => HResult::E_ACCESSDENIED,
+            ErrorKind::AlreadyExists => Win32Error::ERROR_ALREADY_EXISTS.to_hresult(),
+            ErrorKind::InvalidInput => HResult::E_INVALIDARG,
+            ErrorKind::InvalidData => Win32Error::ERROR_INVALID_DATA.to_hresult(),
+            ErrorKind::OutOfMemory => HResult::E_OUTOFMEMORY,
+            ErrorKind::Unsupported => HResult::E_NOTIMPL,
+            ErrorKind::WouldBlock => HResult::E_PENDING,
+            ErrorKind::UnexpectedEof => Win32Error::ERROR_HANDLE_EOF.to_hresult(),
+            ErrorKind::BrokenPipe => Win32Error::ERROR_BROKEN_PIPE.to_hresult(),
+            ErrorKind::TimedOut => Win32Error::ERROR_TIMEOUT.to_hresult(),
+            _ => HResult::E_FAIL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_test() {
+        assert_eq!(HResult::E_ACCESSDENIED.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(HResult::STG_E_FILENOTFOUND.kind(), ErrorKind::NotFound);
+        assert_eq!(
+            Win32Error::ERROR_PATH_NOT_FOUND.to_hresult().kind(),
+            ErrorKind::NotFound
+        );
+        assert_eq!(HResult::E_OUTOFMEMORY.kind(), ErrorKind::OutOfMemory);
+        assert_eq!(HResult::E_FAIL.kind(), ErrorKind::Other);
+        assert_eq!(HResult::E_ABORT.kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn to_io_error_test() {
+        let e = io::Error::from(HResult::STG_E_FILENOTFOUND);
+        assert_eq!(e.kind(), ErrorKind::NotFound);
+        assert_eq!(
+            e.get_ref().unwrap().downcast_ref::<HResult>(),
+            Some(&HResult::STG_E_FILENOTFOUND)
+        );
+        assert_eq!(HResult::from(e), HResult::STG_E_FILENOTFOUND);
+        let e = io::Error::from(HResult::E_FAIL);
+        assert_eq!(e.kind(), ErrorKind::Other);
+        assert_eq!(HResult::from(e), HResult::E_FAIL);
+    }
+
+    #[test]
+    fn from_io_error_test() {
+        let e = io::Error::new(ErrorKind::PermissionDenied, "denied");
+        assert_eq!(HResult::from(e), HResult::E_ACCESSDENIED);
+        let e = io::Error::from(ErrorKind::NotFound);
+        assert_eq!(HResult::from(e), HResult(0x80070002u32 as i32));
+        let e = io::Error::from(ErrorKind::Other);
+        assert_eq!(HResult::from(e), HResult::E_FAIL);
+        let e = io::Error::from(ErrorKind::Interrupted);
+        assert_eq!(HResult::from(e), HResult::E_FAIL);
+    }
+
+    #[test]
+    fn abort_test() {
+        struct Aborted;
+
+        impl io::Read for Aborted {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(HResult::E_ABORT.into())
+            }
+        }
+
+        let e = io::Read::read_exact(&mut Aborted, &mut [0; 4]).unwrap_err();
+        assert_eq!(HResult::from(e), HResult::E_ABORT);
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn raw_os_error_test() {
+        let e = io::Error::from_raw_os_error(5);
+        assert_eq!(HResult::from(e), HResult::E_ACCESSDENIED);
+    }
+
+    #[test]
+    fn question_mark_test() {
+        fn read() -> io::Result<()> {
+            Err(HResult::E_ACCESSDENIED)?;
+            Ok(())
+        }
+        fn com() -> Result<(), HResult> {
+            read()?;
+            Ok(())
+        }
+        assert_eq!(com(), Err(HResult::E_ACCESSDENIED));
+    }
+}
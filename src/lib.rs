@@ -4,89 +4,85 @@
 //! Creates a ComPtr from `CreateDXGIFactory1` function.
 //!
 //! ```
+//! # #[cfg(windows)]
+//! # mod example {
 //! use winapi::shared::dxgi::*;
-//! use winapi::um::winnt::HRESULT;
 //! use winapi::Interface;
-//! use com_ptr::{ComPtr, HResult, hresult};
+//! use com_ptr::{ComPtr, HResult};
 //!
 //! fn create_dxgi_factory<T: Interface>() -> Result<ComPtr<T>, HResult> {
-//!     ComPtr::new(|| {
-//!         let mut obj = std::ptr::null_mut();
-//!         let res = unsafe { CreateDXGIFactory1(&T::uuidof(), &mut obj) };
-//!         hresult(obj as *mut T, res)
-//!     })
+//!     unsafe { ComPtr::from_iid_out(|iid, obj| CreateDXGIFactory1(iid, obj)) }
 //! }
+//! # }
 //! ```
 //!
-#![cfg(windows)]
-
-use std::ops::Deref;
-use std::ptr::{null_mut, NonNull};
-use winapi::shared::guiddef::REFCLSID;
-use winapi::shared::minwindef::DWORD;
-use winapi::um::combaseapi::CoCreateInstance;
-use winapi::um::unknwnbase::IUnknown;
-use winapi::um::winnt::HRESULT;
-use winapi::um::winbase::*;
-use winapi::Interface;
-
-/// A object that wraps HRESULT.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-#[repr(transparent)]
-pub struct HResult(pub HRESULT);
+//! `ComPtr` and `HResult` are available on all platforms. On other platforms than Windows,
+//! `co_create_instance` and `ComApartment` use the in-process emulation of [`Backend::Emulated`].
+//! The COM types used on other platforms are defined in the [`sys`] module.
+//!
+//! COM interfaces can be declared with [`com_interface!`],
+//! and COM objects can be implemented in Rust with [`com_class`] and [`com_impl`].
+//! Mock objects of COM interfaces for tests can be declared with [`mock_com!`].
+//! With the `track-refs` feature, the references owned by `ComPtr` are recorded by the `tracker`
+//! module to find leaks.
 
-impl HResult {
-    #[inline]
-    pub fn is_succeed(&self) -> bool {
-        self.0 >= 0
-    }
-    
-    #[inline]
-    pub fn is_failed(&self) -> bool {
-        self.0 < 0
-    }
-    
-    #[inline]
-    pub fn code(&self) -> HRESULT {
-        self.0
-    }
-}
+extern crate self as com_ptr;
 
-impl std::fmt::Display for HResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        unsafe {
-            let mut p: *mut u16 = std::ptr::null_mut();
-            let len = FormatMessageW(
-                FORMAT_MESSAGE_ALLOCATE_BUFFER | FORMAT_MESSAGE_FROM_SYSTEM,
-                std::ptr::null(),
-                self.0 as u32,
-                0,
-                std::mem::transmute(&mut p),
-                0,
-                std::ptr::null_mut()
-            );
-            let buffer = std::slice::from_raw_parts(p, len as usize);
-            let ret = write!(f, "{}", String::from_utf16_lossy(buffer));
-            LocalFree(p as _);
-            ret
-        }
-    }
-}
+#[macro_use]
+mod interface;
+mod agile;
+mod apartment;
+pub mod class;
+mod com_ref;
+mod context;
+mod error;
+#[cfg(test)]
+mod fixture;
+mod git;
+mod guid;
+mod hresult;
+mod identity;
+#[cfg(any(feature = "windows", feature = "windows-sys"))]
+pub mod interop;
+mod io;
+pub mod message;
+pub mod mock;
+mod registry;
+mod status;
+#[cfg(feature = "hresult-table")]
+mod table;
+pub mod sys;
+#[cfg(feature = "track-refs")]
+pub mod tracker;
 
-impl std::error::Error for HResult {}
+pub use agile::{Agile, ThreadSafe};
+pub use apartment::{ApartmentError, ApartmentInit, ApartmentKind, ComApartment};
+pub use com_ptr_macros::{com_class, com_impl};
+pub use com_ref::ComRef;
+#[doc(hidden)]
+pub use context::hresult_context;
+pub use context::{ContextError, ResultExt};
+pub use error::ComError;
+pub use git::GitCookie;
+pub use guid::{Guid, ParseGuidError};
+#[doc(hidden)]
+pub use interface::IsOrInherits;
+pub use interface::{ComInterface, Inherits};
+pub use hresult::{hresult, Facility, HResult, OtherFacility, ParseHResultError, Severity};
+pub use identity::IdentityKey;
+pub use registry::{register_class, Backend, ClassRegistration};
+pub use status::{NtStatus, Win32Error};
 
-/// Returns a object when success.
-///
-/// If `res` is success, returns a object. OtherWise, returns a HResult object.
-pub fn hresult<T>(obj: T, res: HRESULT) -> Result<T, HResult> {
-    if res < 0 {
-        Err(HResult(res))
-    } else {
-        Ok(obj)
-    }
-}
+use std::ops::Deref;
+use std::ptr::{null_mut, NonNull};
+use sys::{c_void, IUnknown, Interface, GUID, HRESULT};
+use sys::{DWORD, REFCLSID};
+#[cfg(windows)]
+use winapi::um::combaseapi::CoCreateInstance;
 
 /// A smart pointer for COM Interfaces.
+///
+/// `ComPtr<T>` is `Send` and `Sync` only when `T` implements [`Agile`].
 pub struct ComPtr<T: Interface> {
     p: NonNull<T>,
 }
@@ -96,6 +92,9 @@ impl<T: Interface> ComPtr<T> {
     ///
     /// ## Safety
     /// 'f' must returns non-null.
+    ///
+    /// [`ComPtr::from_out`] and [`ComPtr::from_iid_out`] return `E_POINTER` instead of panicking.
+    #[deprecated(note = "use `ComPtr::from_out` or `ComPtr::from_iid_out` instead")]
     pub fn new<F, E>(f: F) -> Result<ComPtr<T>, E>
     where
         F: FnOnce() -> Result<*mut T, E>,
@@ -103,15 +102,103 @@ impl<T: Interface> ComPtr<T> {
         unsafe { Ok(ComPtr::from_raw(f()?)) }
     }
 
+    /// Creates a new ComPtr from an out-parameter.
+    ///
+    /// `f` receives a null pointer and returns the HRESULT of the API that writes the interface.
+    /// Returns the HRESULT when it is a failure, and `E_POINTER` when the interface is null.
+    ///
+    /// ```
+    /// # #[cfg(windows)]
+    /// # fn example() -> Result<(), com_ptr::HResult> {
+    /// use winapi::um::objidlbase::IMalloc;
+    /// use winapi::um::combaseapi::CoGetMalloc;
+    /// use com_ptr::ComPtr;
+    ///
+    /// let malloc = unsafe { ComPtr::<IMalloc>::from_out(|pp| CoGetMalloc(1, pp))? };
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Safety
+    /// When `f` returns a success, the written pointer must be null or a pointer to `T`
+    /// with a reference that is transferred to the `ComPtr`.
+    #[inline]
+    pub unsafe fn from_out<F>(f: F) -> Result<ComPtr<T>, HResult>
+    where
+        F: FnOnce(&mut *mut T) -> HRESULT,
+    {
+        ComPtr::from_out_opt(f)?.ok_or(HResult::E_POINTER)
+    }
+
+    /// The same as [`ComPtr::from_out`] but returns `None` when the interface is null.
+    ///
+    /// ## Safety
+    /// The same as [`ComPtr::from_out`].
+    pub unsafe fn from_out_opt<F>(f: F) -> Result<Option<ComPtr<T>>, HResult>
+    where
+        F: FnOnce(&mut *mut T) -> HRESULT,
+    {
+        let mut p = null_mut();
+        hresult((), f(&mut p))?;
+        Ok(NonNull::new(p).map(ComPtr::acquire))
+    }
+
+    /// Creates a new ComPtr from an API that takes the IID and the out-parameter.
+    ///
+    /// `f` receives the IID of `T` and a null pointer.
+    /// Returns the HRESULT when it is a failure, and `E_POINTER` when the interface is null.
+    ///
+    /// ```
+    /// # #[cfg(windows)]
+    /// # fn example() -> Result<(), com_ptr::HResult> {
+    /// use winapi::shared::dxgi::{CreateDXGIFactory1, IDXGIFactory1};
+    /// use com_ptr::ComPtr;
+    ///
+    /// let factory =
+    ///     unsafe { ComPtr::<IDXGIFactory1>::from_iid_out(|iid, ppv| CreateDXGIFactory1(iid, ppv))? };
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Safety
+    /// When `f` returns a success, the written pointer must be null or a pointer to the interface
+    /// of the IID with a reference that is transferred to the `ComPtr`.
+    #[inline]
+    pub unsafe fn from_iid_out<F>(f: F) -> Result<ComPtr<T>, HResult>
+    where
+        F: FnOnce(&GUID, &mut *mut c_void) -> HRESULT,
+    {
+        ComPtr::from_iid_out_opt(f)?.ok_or(HResult::E_POINTER)
+    }
+
+    /// The same as [`ComPtr::from_iid_out`] but returns `None` when the interface is null.
+    ///
+    /// ## Safety
+    /// The same as [`ComPtr::from_iid_out`].
+    pub unsafe fn from_iid_out_opt<F>(f: F) -> Result<Option<ComPtr<T>>, HResult>
+    where
+        F: FnOnce(&GUID, &mut *mut c_void) -> HRESULT,
+    {
+        let mut p = null_mut();
+        hresult((), f(&T::uuidof(), &mut p))?;
+        Ok(NonNull::new(p as *mut T).map(ComPtr::acquire))
+    }
+
     /// Creates a new ComPtr from a raw pointer.
     ///
     /// ## Safety
     /// 'ptr' must be non-null.
     #[inline]
     pub unsafe fn from_raw(ptr: *mut T) -> ComPtr<T> {
-        ComPtr {
-            p: NonNull::new(ptr).expect("ComPtr should not be null."),
-        }
+        ComPtr::acquire(NonNull::new(ptr).expect("ComPtr should not be null."))
+    }
+
+    #[inline]
+    fn acquire(p: NonNull<T>) -> ComPtr<T> {
+        let p = ComPtr { p };
+        #[cfg(feature = "track-refs")]
+        tracker::acquire(&p, tracker::RefKind::New);
+        p
     }
 
     /// Returns a pointer
@@ -122,11 +209,7 @@ impl<T: Interface> ComPtr<T> {
 
     /// Returns a `ComPtr<U>` when interface `T` support interface `U`.
     pub fn query_interface<U: Interface>(&self) -> Result<ComPtr<U>, HResult> {
-        unsafe {
-            let mut p = null_mut();
-            let res = self.as_unknown().QueryInterface(&U::uuidof(), &mut p);
-            hresult(ComPtr::from_raw(p as *mut U), res)
-        }
+        unsafe { ComPtr::from_iid_out(|iid, p| self.as_unknown().QueryInterface(iid, p)) }
     }
 
     #[inline]
@@ -134,19 +217,107 @@ impl<T: Interface> ComPtr<T> {
         unsafe { &*(self.as_ptr() as *mut IUnknown) }
     }
 
+    /// Converts into a `ComPtr` of the base interface without `QueryInterface`.
+    ///
+    /// The pointer and the reference are reused.
+    #[inline]
+    pub fn upcast<U: Interface>(self) -> ComPtr<U>
+    where
+        T: Inherits<U>,
+    {
+        #[cfg(feature = "track-refs")]
+        tracker::upcast::<T, U>(&self);
+        let p = self.p.cast();
+        std::mem::forget(self);
+        ComPtr { p }
+    }
+
     /// Increases a reference count.
     #[inline]
     pub fn add_ref(&self) {
         unsafe { self.as_unknown().AddRef() };
+        #[cfg(feature = "track-refs")]
+        tracker::acquire(self, tracker::RefKind::AddRef);
     }
 
-    /// Decreases a reference count.
+    /// Decreases a reference count and returns the remaining count.
+    ///
+    /// The returned count is intended for tests and debugging.
     ///
     /// ## Safety
     /// The reference count greater than 0.
     #[inline]
-    pub unsafe fn release(&self) {
-        self.as_unknown().Release();
+    pub unsafe fn release(&self) -> u32 {
+        #[cfg(feature = "track-refs")]
+        tracker::release(self, true);
+        self.as_unknown().Release()
+    }
+
+    /// Returns a pointer and transfers the reference to the caller like `Detach` of ATL.
+    ///
+    /// The reference must be released by the caller or by `ComPtr::from_raw`.
+    #[inline]
+    pub fn into_raw(self) -> *mut T {
+        #[cfg(feature = "track-refs")]
+        tracker::release(&self, false);
+        let p = self.as_ptr();
+        std::mem::forget(self);
+        p
+    }
+
+    /// Leaks the reference and returns a `ComRef` that is valid for the rest of the program.
+    #[inline]
+    pub fn forget(self) -> ComRef<'static, T> {
+        unsafe { ComRef::from_raw(self.into_raw()) }
+    }
+
+    /// Takes the reference of `raw` without `AddRef` like `Attach` of ATL.
+    ///
+    /// The previous interface in `dst` is released. `dst` becomes `None` when `raw` is null.
+    ///
+    /// ## Safety
+    /// `raw` must be null or a valid pointer with a reference.
+    #[inline]
+    pub unsafe fn attach(dst: &mut Option<ComPtr<T>>, raw: *mut T) {
+        *dst = NonNull::new(raw).map(ComPtr::acquire);
+    }
+
+    /// Writes the pointer to an out-parameter of a COM method and transfers the reference.
+    ///
+    /// Returns `E_POINTER` and releases the reference when `pp` is null. Otherwise, returns `S_OK`.
+    ///
+    /// ## Safety
+    /// `pp` must be null or valid for writes.
+    #[inline]
+    pub unsafe fn write_out(self, pp: *mut *mut T) -> HRESULT {
+        if pp.is_null() {
+            return sys::E_POINTER;
+        }
+        *pp = self.into_raw();
+        sys::S_OK
+    }
+
+    /// Returns `true` when `self` has the only reference of the object.
+    ///
+    /// This is available only with `debug_assertions`
+    /// because the reference count of COM is intended for tests and debugging.
+    #[cfg(debug_assertions)]
+    pub fn is_unique(&self) -> bool {
+        self.add_ref();
+        unsafe { self.release() == 1 }
+    }
+
+    /// Returns the pointer with the only reference when `self` is unique.
+    /// Otherwise, returns `self`.
+    ///
+    /// This is available only with `debug_assertions` like [`ComPtr::is_unique`].
+    #[cfg(debug_assertions)]
+    pub fn try_unwrap(self) -> Result<*mut T, ComPtr<T>> {
+        if self.is_unique() {
+            Ok(self.into_raw())
+        } else {
+            Err(self)
+        }
     }
 }
 
@@ -160,15 +331,20 @@ impl<T: Interface> Deref for ComPtr<T> {
 
 impl<T: Interface> Clone for ComPtr<T> {
     fn clone(&self) -> Self {
-        self.add_ref();
-        ComPtr { p: self.p }
+        unsafe { self.as_unknown().AddRef() };
+        let p = ComPtr { p: self.p };
+        #[cfg(feature = "track-refs")]
+        tracker::acquire(&p, tracker::RefKind::Clone);
+        p
     }
 }
 
 impl<T: Interface> Drop for ComPtr<T> {
     fn drop(&mut self) {
+        #[cfg(feature = "track-refs")]
+        tracker::release(self, false);
         unsafe {
-            self.release();
+            self.as_unknown().Release();
         }
     }
 }
@@ -183,7 +359,7 @@ impl<T: Interface> Eq for ComPtr<T> {}
 
 impl<T: Interface> PartialOrd for ComPtr<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.as_ptr().partial_cmp(&other.as_ptr())
+        Some(self.cmp(other))
     }
 }
 
@@ -205,37 +381,249 @@ impl<T: Interface> std::convert::AsRef<T> for ComPtr<T> {
     }
 }
 
-unsafe impl<T: Interface> Send for ComPtr<T> {}
-unsafe impl<T: Interface> Sync for ComPtr<T> {}
+/// Converts without `QueryInterface` like [`ComPtr::upcast`].
+///
+/// `From` is implemented only for the base interfaces in this crate. Use [`ComPtr::upcast`] for
+/// other base interfaces.
+impl<T: Inherits<IUnknown>> From<ComPtr<T>> for ComPtr<IUnknown> {
+    #[inline]
+    fn from(src: ComPtr<T>) -> ComPtr<IUnknown> {
+        src.upcast()
+    }
+}
 
 /// Creates a ComPtr of the class associated with a specified CLSID.
+///
+/// With [`Backend::Emulated`], the class is created from the factory registered by
+/// [`register_class`] and `clsctx` is ignored.
 pub fn co_create_instance<T: Interface>(
     clsid: REFCLSID,
     outer: Option<*mut IUnknown>,
     clsctx: DWORD,
 ) -> Result<ComPtr<T>, HResult> {
-    ComPtr::new(|| {
-        let mut obj = null_mut();
-        let outer = match outer {
-            Some(p) => p,
-            None => null_mut(),
-        };
-        let res = unsafe { CoCreateInstance(clsid, outer, clsctx, &T::uuidof(), &mut obj) };
-        hresult(obj as *mut T, res)
-    })
+    #[cfg(windows)]
+    {
+        if Backend::current() == Backend::Com {
+            let outer = match outer {
+                Some(p) => p,
+                None => null_mut(),
+            };
+            return unsafe {
+                ComPtr::from_iid_out(|iid, obj| CoCreateInstance(clsid, outer, clsctx, iid, obj))
+            };
+        }
+    }
+    let _ = clsctx;
+    registry::create_instance(clsid, outer)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use sys::*;
+    #[cfg(windows)]
     use winapi::shared::wtypesbase::CLSCTX_INPROC_SERVER;
-    use winapi::um::objbase::CoInitialize;
+    #[cfg(windows)]
     use winapi::um::wincodec::*;
 
+    #[repr(C)]
+    struct TestObject {
+        vtbl: *const IUnknownVtbl,
+        count: AtomicU32,
+    }
+
+    static TEST_OBJECT_VTBL: IUnknownVtbl = IUnknownVtbl {
+        QueryInterface: test_object_query_interface,
+        AddRef: test_object_add_ref,
+        Release: test_object_release,
+    };
+
+    unsafe extern "system" fn test_object_query_interface(
+        this: *mut IUnknown,
+        riid: REFIID,
+        ppv: *mut *mut c_void,
+    ) -> HRESULT {
+        if IsEqualGUID(&*riid, &IUnknown::uuidof()) {
+            test_object_add_ref(this);
+            *ppv = this as *mut c_void;
+            S_OK
+        } else {
+            *ppv = null_mut();
+            E_NOINTERFACE
+        }
+    }
+
+    unsafe extern "system" fn test_object_add_ref(this: *mut IUnknown) -> ULONG {
+        let obj = &*(this as *const TestObject);
+        obj.count.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    unsafe extern "system" fn test_object_release(this: *mut IUnknown) -> ULONG {
+        let obj = &*(this as *const TestObject);
+        obj.count.fetch_sub(1, Ordering::Relaxed) - 1
+    }
+
+    fn new_test_object() -> Box<TestObject> {
+        Box::new(TestObject {
+            vtbl: &TEST_OBJECT_VTBL,
+            count: AtomicU32::new(1),
+        })
+    }
+
+    #[test]
+    fn ref_count_test() {
+        let obj = new_test_object();
+        let p = unsafe { ComPtr::from_raw(&*obj as *const TestObject as *mut IUnknown) };
+        p.add_ref();
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        let q = p.clone();
+        assert_eq!(obj.count.load(Ordering::Relaxed), 3);
+        assert!(p == q);
+        drop(q);
+        unsafe { p.release() };
+        assert_eq!(obj.count.load(Ordering::Relaxed), 1);
+        drop(p);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn ownership_test() {
+        let obj = new_test_object();
+        let raw = &*obj as *const TestObject as *mut IUnknown;
+        let p = unsafe { ComPtr::from_raw(raw) };
+        p.add_ref();
+        assert_eq!(unsafe { p.release() }, 1);
+        assert_eq!(p.clone().into_raw(), raw);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        let mut slot = Some(p.clone());
+        unsafe { ComPtr::attach(&mut slot, raw) };
+        assert!(slot.as_ref() == Some(&p));
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        unsafe { ComPtr::attach(&mut slot, null_mut()) };
+        assert!(slot.is_none());
+        assert_eq!(obj.count.load(Ordering::Relaxed), 1);
+        let r = p.clone().forget();
+        assert!(r == p);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        unsafe { r.Release() };
+        drop(p);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn write_out_test() {
+        let obj = new_test_object();
+        let p = unsafe { ComPtr::from_raw(&*obj as *const TestObject as *mut IUnknown) };
+        let mut out = null_mut();
+        assert_eq!(unsafe { p.clone().write_out(&mut out) }, S_OK);
+        assert_eq!(out, p.as_ptr());
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        drop(unsafe { ComPtr::from_raw(out) });
+        assert_eq!(unsafe { p.clone().write_out(null_mut()) }, E_POINTER);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn unique_test() {
+        let obj = new_test_object();
+        let p = unsafe { ComPtr::from_raw(&*obj as *const TestObject as *mut IUnknown) };
+        assert!(p.is_unique());
+        let q = p.clone();
+        assert!(!p.is_unique());
+        let p = p.try_unwrap().unwrap_err();
+        drop(q);
+        let raw = p.try_unwrap().unwrap();
+        assert_eq!(raw as *const TestObject, &*obj as *const TestObject);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn query_interface_test() {
+        let obj = new_test_object();
+        let p = unsafe { ComPtr::from_raw(&*obj as *const TestObject as *mut IUnknown) };
+        let q = p.query_interface::<IUnknown>().unwrap();
+        assert!(p == q);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            p.query_interface::<ISupportErrorInfo>(),
+            Err(HResult(E_NOINTERFACE))
+        );
+        drop(q);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 1);
+        drop(p);
+    }
+
+    #[test]
+    fn from_out_test() {
+        let obj = new_test_object();
+        let p = unsafe {
+            ComPtr::<IUnknown>::from_out(|pp| {
+                *pp = &*obj as *const TestObject as *mut IUnknown;
+                S_OK
+            })
+        }
+        .unwrap();
+        assert_eq!(p.as_ptr() as *const TestObject, &*obj as *const TestObject);
+        unsafe {
+            assert_eq!(
+                ComPtr::<IUnknown>::from_out(|_| E_NOINTERFACE),
+                Err(HResult(E_NOINTERFACE))
+            );
+            assert_eq!(
+                ComPtr::<IUnknown>::from_out(|_| S_OK),
+                Err(HResult::E_POINTER)
+            );
+            assert_eq!(ComPtr::<IUnknown>::from_out_opt(|_| S_FALSE), Ok(None));
+        }
+        drop(p);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn from_iid_out_test() {
+        let obj = new_test_object();
+        let p = unsafe {
+            ComPtr::<IUnknown>::from_iid_out(|iid, ppv| {
+                test_object_query_interface(&*obj as *const TestObject as *mut IUnknown, iid, ppv)
+            })
+        }
+        .unwrap();
+        assert_eq!(obj.count.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            unsafe { ComPtr::<IUnknown>::from_iid_out(|_, _| S_OK) },
+            Err(HResult::E_POINTER)
+        );
+        let q = unsafe {
+            ComPtr::<IUnknown>::from_iid_out_opt(|iid, ppv| {
+                assert!(IsEqualGUID(iid, &IUnknown::uuidof()));
+                *ppv = p.as_ptr() as *mut c_void;
+                p.add_ref();
+                S_OK
+            })
+        }
+        .unwrap();
+        assert!(q.as_ref() == Some(&p));
+        drop(q);
+        drop(p);
+        assert_eq!(obj.count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn hresult_test() {
+        assert!(HResult(S_OK).is_succeed());
+        assert!(HResult(S_FALSE).is_succeed());
+        assert!(HResult(E_NOINTERFACE).is_failed());
+        assert_eq!(hresult(1, S_OK), Ok(1));
+        assert_eq!(hresult(1, E_NOINTERFACE), Err(HResult(E_NOINTERFACE)));
+    }
+
     #[test]
+    #[cfg(windows)]
     #[allow(clippy::eq_op)]
     fn co_create_instance_test() {
-        unsafe { CoInitialize(null_mut()) };
+        let _apartment = ComApartment::sta().unwrap();
 
         let p = co_create_instance::<IWICImagingFactory>(
             &CLSID_WICImagingFactory,
@@ -243,7 +631,7 @@ mod tests {
             CLSCTX_INPROC_SERVER,
         );
         if let Err(res) = p {
-            panic!("HRESULT: 0x{:<08x}", res.code());
+            panic!("HRESULT: {:#010x}", res);
         }
         assert!(p == p);
         assert!(p <= p);
@@ -267,6 +655,6 @@ mod tests {
     #[ignore]
     fn display_test() {
         let ret = HResult(0);
-        println!("0x{:<08x} {}", ret.code(), ret);
+        println!("{:#010x} {}", ret, ret);
     }
 }
@@ -0,0 +1,305 @@
+//! Looking up the messages of HRESULT.
+//!
+//! `HResult` looks up its message from the sources registered for its facility, then the sources
+//! registered for all facilities. On Windows, the system message table and the message tables of
+//! some modules such as `wininet.dll` and `winhttp.dll` are registered by default.
+//! When no source has the message, `HResult` is displayed as `HRESULT 0x887A0005`.
+
+use crate::sys::*;
+use crate::{Facility, HResult};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+
+/// A source of the messages of HRESULT.
+pub trait MessageSource {
+    /// Returns the message of `code` in the language `lang_id`.
+    ///
+    /// `lang_id` is 0 when the language is not specified.
+    fn message(&self, code: HRESULT, lang_id: u32) -> Option<String>;
+}
+
+impl<F> MessageSource for F
+where
+    F: Fn(HRESULT, u32) -> Option<String>,
+{
+    #[inline]
+    fn message(&self, code: HRESULT, lang_id: u32) -> Option<String> {
+        self(code, lang_id)
+    }
+}
+
+type Source = Arc<dyn MessageSource + Send + Sync>;
+
+static SOURCES: Mutex<Vec<(Option<Facility>, Source)>> = Mutex::new(Vec::new());
+static DEFAULT_SOURCES: Once = Once::new();
+static LANGUAGE: AtomicU32 = AtomicU32::new(0);
+
+fn sources() -> MutexGuard<'static, Vec<(Option<Facility>, Source)>> {
+    SOURCES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Registers a message source for `facility`.
+///
+/// When `facility` is `None`, the source is used for all facilities.
+/// The sources registered later are used earlier.
+pub fn register_message_source<S>(facility: Option<Facility>, source: S)
+where
+    S: MessageSource + Send + Sync + 'static,
+{
+    register_default_sources();
+    sources().insert(0, (facility, Arc::new(source)));
+}
+
+/// Sets the language of messages. 0 means the default language.
+///
+/// When no source has the message in the language, the message in the default language is used.
+#[inline]
+pub fn set_message_language(lang_id: u32) {
+    LANGUAGE.store(lang_id, Ordering::Relaxed);
+}
+
+/// Returns the language of messages.
+#[inline]
+pub fn message_language() -> u32 {
+    LANGUAGE.load(Ordering::Relaxed)
+}
+
+/// Returns the trimmed message of `hr` from the registered sources.
+///
+/// The sources are called without the lock, so that they can format other `HResult`s.
+pub(crate) fn lookup(hr: HResult) -> Option<String> {
+    lookup_in(hr, message_language())
+}
+
+fn lookup_in(hr: HResult, lang_id: u32) -> Option<String> {
+    register_default_sources();
+    let facility = hr.facility();
+    let sources = {
+        let sources = sources();
+        let specific = sources.iter().filter(|(f, _)| *f == Some(facility));
+        let general = sources.iter().filter(|(f, _)| f.is_none());
+        specific
+            .chain(general)
+            .map(|(_, source)| source.clone())
+            .collect::<Vec<_>>()
+    };
+    let find = |lang_id| {
+        sources
+            .iter()
+            .filter_map(|source| source.message(hr.0, lang_id))
+            .map(|message| message.trim_end().to_string())
+            .find(|message| !message.is_empty())
+    };
+    match lang_id {
+        0 => find(0),
+        lang_id => find(lang_id).or_else(|| find(0)),
+    }
+}
+
+/// Writes the fallback form of `hr`.
+pub(crate) fn fmt_fallback(hr: HResult, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "HRESULT 0x{:08X}", hr.0)
+}
+
+#[cfg(windows)]
+fn register_default_sources() {
+    DEFAULT_SOURCES.call_once(|| {
+        let mut sources = sources();
+        sources.push((None, Arc::new(SystemMessageSource)));
+        let modules = [
+            (Facility::Win32, "wininet.dll"),
+            (Facility::Win32, "winhttp.dll"),
+            (Facility::Dxgi, "dxgi.dll"),
+            (Facility::Direct3D11, "d3d11.dll"),
+            (Facility::Direct3D12, "d3d12.dll"),
+        ];
+        for (facility, name) in modules.iter() {
+            if let Ok(source) = ModuleMessageSource::new(name) {
+                sources.push((Some(*facility), Arc::new(source)));
+            }
+        }
+    });
+}
+
+#[cfg(not(windows))]
+fn register_default_sources() {
+    DEFAULT_SOURCES.call_once(|| {});
+}
+
+#[cfg(windows)]
+fn format_message(flags: DWORD, source: *const c_void, code: HRESULT, lang_id: u32) -> Option<String> {
+    use winapi::um::winbase::*;
+
+    unsafe {
+        let mut p: *mut u16 = std::ptr::null_mut();
+        let len = FormatMessageW(
+            flags | FORMAT_MESSAGE_ALLOCATE_BUFFER | FORMAT_MESSAGE_IGNORE_INSERTS,
+            source,
+            code as u32,
+            lang_id,
+            &mut p as *mut *mut u16 as *mut u16,
+            0,
+            std::ptr::null_mut(),
+        );
+        if len == 0 || p.is_null() {
+            return None;
+        }
+        let buffer = std::slice::from_raw_parts(p, len as usize);
+        let message = String::from_utf16_lossy(buffer);
+        LocalFree(p as _);
+        Some(message)
+    }
+}
+
+/// The message source of the system message table.
+#[cfg(windows)]
+#[derive(Clone, Copy, Debug)]
+pub struct SystemMessageSource;
+
+#[cfg(windows)]
+impl MessageSource for SystemMessageSource {
+    fn message(&self, code: HRESULT, lang_id: u32) -> Option<String> {
+        use winapi::um::winbase::FORMAT_MESSAGE_FROM_SYSTEM;
+
+        format_message(FORMAT_MESSAGE_FROM_SYSTEM, std::ptr::null(), code, lang_id)
+    }
+}
+
+/// The message source of the message table in a module.
+#[cfg(windows)]
+#[derive(Debug)]
+pub struct ModuleMessageSource {
+    module: winapi::shared::minwindef::HMODULE,
+}
+
+#[cfg(windows)]
+impl ModuleMessageSource {
+    /// Loads a module as a data file.
+    pub fn new(name: &str) -> Result<ModuleMessageSource, HResult> {
+        use std::ffi::OsStr;
+        use std::os::windows::ffi::OsStrExt;
+        use winapi::um::libloaderapi::*;
+
+        let name = OsStr::new(name)
+            .encode_wide()
+            .chain(Some(0))
+            .collect::<Vec<_>>();
+        let module = unsafe {
+            LoadLibraryExW(name.as_ptr(), std::ptr::null_mut(), LOAD_LIBRARY_AS_DATAFILE)
+        };
+        if module.is_null() {
+            return Err(crate::Win32Error::last().into());
+        }
+        Ok(ModuleMessageSource { module })
+    }
+}
+
+#[cfg(windows)]
+impl MessageSource for ModuleMessageSource {
+    fn message(&self, code: HRESULT, lang_id: u32) -> Option<String> {
+        use winapi::um::winbase::FORMAT_MESSAGE_FROM_HMODULE;
+
+        format_message(FORMAT_MESSAGE_FROM_HMODULE, self.module as _, code, lang_id).or_else(|| {
+            // The message tables of modules often have Win32 codes instead of HRESULTs.
+            if HResult(code).facility() == Facility::Win32 {
+                let code = HResult(code).code_part() as HRESULT;
+                format_message(FORMAT_MESSAGE_FROM_HMODULE, self.module as _, code, lang_id)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ModuleMessageSource {
+    fn drop(&mut self) {
+        unsafe { winapi::um::libloaderapi::FreeLibrary(self.module) };
+    }
+}
+
+#[cfg(windows)]
+unsafe impl Send for ModuleMessageSource {}
+#[cfg(windows)]
+unsafe impl Sync for ModuleMessageSource {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_test() {
+        register_message_source(Some(Facility::from_u16(0x1a0)), |code, _| {
+            if code == 0x81a00001u32 as HRESULT {
+                Some("Specific message.\r\n".to_string())
+            } else if code == 0x81a00002u32 as HRESULT {
+                Some("  \r\n".to_string())
+            } else {
+                None
+            }
+        });
+        assert_eq!(
+            HResult(0x81a00001u32 as HRESULT).to_string(),
+            "Specific message."
+        );
+        assert_eq!(
+            HResult(0x81a00002u32 as HRESULT).to_string(),
+            "HRESULT 0x81A00002"
+        );
+        assert_eq!(
+            HResult(0x81a10001u32 as HRESULT).to_string(),
+            "HRESULT 0x81A10001"
+        );
+    }
+
+    #[test]
+    fn language_test() {
+        register_message_source(Some(Facility::from_u16(0x1a2)), |code, lang_id| {
+            if code == 0x81a20001u32 as HRESULT {
+                Some(format!("Language {}", lang_id))
+            } else {
+                None
+            }
+        });
+        set_message_language(0x0409);
+        assert_eq!(message_language(), 0x0409);
+        assert_eq!(
+            lookup(HResult(0x81a20001u32 as HRESULT)),
+            Some("Language 1033".to_string())
+        );
+        set_message_language(0);
+    }
+
+    #[test]
+    fn default_language_test() {
+        register_message_source(Some(Facility::from_u16(0x1a3)), |code, lang_id| {
+            if code == 0x81a30001u32 as HRESULT && lang_id == 0 {
+                Some("Default language".to_string())
+            } else {
+                None
+            }
+        });
+        assert_eq!(
+            lookup_in(HResult(0x81a30001u32 as HRESULT), 0x0411),
+            Some("Default language".to_string())
+        );
+        assert_eq!(lookup_in(HResult(0x81a30002u32 as HRESULT), 0x0411), None);
+    }
+
+    #[test]
+    fn reentrant_test() {
+        register_message_source(Some(Facility::from_u16(0x1a4)), |code, _| {
+            if code == 0x81a40001u32 as HRESULT {
+                Some(format!("Caused by {}", HResult(0x81a40002u32 as HRESULT)))
+            } else if code == 0x81a40002u32 as HRESULT {
+                Some("the inner error.".to_string())
+            } else {
+                None
+            }
+        });
+        assert_eq!(
+            HResult(0x81a40001u32 as HRESULT).to_string(),
+            "Caused by the inner error."
+        );
+    }
+}
@@ -0,0 +1,674 @@
+//! Mock objects of COM interfaces for unit tests.
+//!
+//! [`mock_com!`](crate::mock_com) declares a mock of an interface. The methods of the mock are
+//! driven by closures, and the calls are counted. A [`Mock`] verifies the expectations and
+//! that all references were released when it is dropped.
+//!
+//! Mocks are not thread-safe, so a mock cannot implement an [`Agile`](crate::Agile) interface.
+//!
+//! # Examples
+//!
+//! ```
+//! use com_ptr::sys::*;
+//! use com_ptr::{com_interface, mock_com, ComPtr};
+//!
+//! com_interface! {
+//!     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+//!     interface ISize(ISizeVtbl): IUnknown(IUnknownVtbl) {
+//!         fn GetSize(width: *mut u32, height: *mut u32) -> HRESULT,
+//!     }
+//! }
+//!
+//! mock_com! {
+//!     struct MockSize: ISize(ISizeVtbl) {
+//!         fn GetSize(width: *mut u32, height: *mut u32) -> HRESULT = E_UNEXPECTED,
+//!     }
+//! }
+//!
+//! fn area(p: &ComPtr<ISize>) -> u32 {
+//!     let (mut width, mut height) = (0, 0);
+//!     unsafe { p.GetSize(&mut width, &mut height) };
+//!     width * height
+//! }
+//!
+//! let mock = MockSize::new();
+//! mock.GetSize.times(1).returning(Box::new(|width, height| unsafe {
+//!     *width = 3;
+//!     *height = 4;
+//!     S_OK
+//! }));
+//! let p = mock.com_ptr::<ISize>();
+//! assert_eq!(area(&p), 12);
+//! ```
+
+use crate::sys::*;
+use crate::ComPtr;
+use std::cell::{Cell, RefCell};
+use std::ops::Deref;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr::NonNull;
+
+/// A method of a mock. `F` is `dyn FnMut` of the parameters of the method.
+pub struct MockMethod<F: ?Sized> {
+    handler: RefCell<Option<Box<F>>>,
+    times: Cell<Option<usize>>,
+    calls: Cell<usize>,
+}
+
+impl<F: ?Sized> MockMethod<F> {
+    /// Sets the closure that is called for each call of the method.
+    ///
+    /// Out-parameters are written through the pointers that the closure receives.
+    #[inline]
+    pub fn returning(&self, f: Box<F>) -> &Self {
+        *self.handler.borrow_mut() = Some(f);
+        self
+    }
+
+    /// Expects that the method is called exactly `n` times.
+    #[inline]
+    pub fn times(&self, n: usize) -> &Self {
+        self.times.set(Some(n));
+        self
+    }
+
+    /// Expects that the method is never called.
+    #[inline]
+    pub fn never(&self) -> &Self {
+        self.times(0)
+    }
+
+    /// Returns the number of the calls.
+    #[inline]
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+
+    #[doc(hidden)]
+    pub fn verify(&self, name: &str, failures: &mut Vec<String>) {
+        if let Some(n) = self.times.get() {
+            if n != self.calls.get() {
+                failures.push(format!(
+                    "{} was expected to be called {} times but was called {} times",
+                    name,
+                    n,
+                    self.calls.get()
+                ));
+            }
+        }
+    }
+}
+
+impl<F: ?Sized> Default for MockMethod<F> {
+    #[inline]
+    fn default() -> Self {
+        MockMethod {
+            handler: RefCell::new(None),
+            times: Cell::new(None),
+            calls: Cell::new(0),
+        }
+    }
+}
+
+impl<F: ?Sized> std::fmt::Debug for MockMethod<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("MockMethod")
+            .field("times", &self.times.get())
+            .field("calls", &self.calls.get())
+            .finish()
+    }
+}
+
+/// The values that the methods of mocks return when the call is unexpected or the closure panics.
+///
+/// The integers return `0`. `HRESULT` and `BOOL` are `i32` and also return `0`, so the methods
+/// returning `HRESULT` declare the failure to return such as `-> HRESULT = E_UNEXPECTED` in
+/// [`mock_com!`](crate::mock_com).
+pub trait MockReturn {
+    fn unexpected() -> Self;
+}
+
+impl MockReturn for () {
+    #[inline]
+    fn unexpected() -> Self {}
+}
+
+impl<T> MockReturn for *mut T {
+    #[inline]
+    fn unexpected() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
+impl<T> MockReturn for *const T {
+    #[inline]
+    fn unexpected() -> Self {
+        std::ptr::null()
+    }
+}
+
+macro_rules! impl_mock_return {
+    ($($t:ty),*) => {
+        $(
+            impl MockReturn for $t {
+                #[inline]
+                fn unexpected() -> Self {
+                    Default::default()
+                }
+            }
+        )*
+    };
+}
+
+impl_mock_return!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+/// The mocks declared by [`mock_com!`](crate::mock_com).
+///
+/// ## Safety
+/// `Vtable` must be the vtable of all interfaces that `is_iid` returns `true`.
+#[doc(hidden)]
+pub unsafe trait MockInterface: Sized + 'static {
+    type Vtable;
+
+    fn vtable() -> Self::Vtable;
+    fn is_iid(iid: &GUID) -> bool;
+    fn verify(&self, failures: &mut Vec<String>);
+}
+
+#[repr(C)]
+#[doc(hidden)]
+pub struct MockObject<M: MockInterface> {
+    vtbl_ptr: *const M::Vtable,
+    vtbl: M::Vtable,
+    refs: Cell<u32>,
+    failures: RefCell<Vec<String>>,
+    mock: M,
+}
+
+impl<M: MockInterface> MockObject<M> {
+    #[inline]
+    unsafe fn from_this<'a, T>(this: *mut T) -> &'a MockObject<M> {
+        &*(this as *const MockObject<M>)
+    }
+
+    fn fail(&self, message: String) {
+        self.failures.borrow_mut().push(message);
+    }
+}
+
+#[doc(hidden)]
+pub unsafe extern "system" fn query_interface<M: MockInterface>(
+    this: *mut IUnknown,
+    riid: REFIID,
+    ppv: *mut *mut c_void,
+) -> HRESULT {
+    if ppv.is_null() {
+        return E_POINTER;
+    }
+    if M::is_iid(&*riid) {
+        add_ref::<M>(this);
+        *ppv = this as *mut c_void;
+        S_OK
+    } else {
+        *ppv = std::ptr::null_mut();
+        E_NOINTERFACE
+    }
+}
+
+#[doc(hidden)]
+pub unsafe extern "system" fn add_ref<M: MockInterface>(this: *mut IUnknown) -> ULONG {
+    let obj = MockObject::<M>::from_this(this);
+    obj.refs.set(obj.refs.get() + 1);
+    obj.refs.get()
+}
+
+#[doc(hidden)]
+pub unsafe extern "system" fn release<M: MockInterface>(this: *mut IUnknown) -> ULONG {
+    let obj = MockObject::<M>::from_this(this);
+    match obj.refs.get() {
+        0 => {
+            obj.fail("Release was called without a reference".to_string());
+            0
+        }
+        n => {
+            obj.refs.set(n - 1);
+            n - 1
+        }
+    }
+}
+
+/// Calls the closure of a method.
+#[doc(hidden)]
+pub unsafe fn call<M, F, R, T>(
+    this: *mut T,
+    name: &'static str,
+    method: impl FnOnce(&M) -> &MockMethod<F>,
+    invoke: impl FnOnce(&mut F) -> R,
+    unexpected: impl FnOnce() -> R,
+) -> R
+where
+    M: MockInterface,
+    F: ?Sized,
+{
+    let obj = MockObject::<M>::from_this(this);
+    let method = method(&obj.mock);
+    method.calls.set(method.calls.get() + 1);
+    let mut handler = match method.handler.try_borrow_mut() {
+        Ok(handler) => handler,
+        Err(_) => {
+            obj.fail(format!("{} was called recursively", name));
+            return unexpected();
+        }
+    };
+    match handler.as_mut() {
+        Some(f) => match catch_unwind(AssertUnwindSafe(|| invoke(f))) {
+            Ok(ret) => ret,
+            Err(_) => {
+                obj.fail(format!("{} panicked", name));
+                unexpected()
+            }
+        },
+        None => {
+            obj.fail(format!("{} was called unexpectedly", name));
+            unexpected()
+        }
+    }
+}
+
+/// A mock object.
+///
+/// `Mock<M>` derefs to `M` that has the methods of the interface as the fields.
+/// When `Mock<M>` is dropped, it panics if the expectations were not met,
+/// a method was called unexpectedly, or a reference was not released.
+pub struct Mock<M: MockInterface> {
+    obj: NonNull<MockObject<M>>,
+}
+
+impl<M: MockInterface> Mock<M> {
+    /// Creates a mock object.
+    pub fn new(mock: M) -> Mock<M> {
+        let obj = Box::new(MockObject {
+            vtbl_ptr: std::ptr::null(),
+            vtbl: M::vtable(),
+            refs: Cell::new(0),
+            failures: RefCell::new(Vec::new()),
+            mock,
+        });
+        let obj = Box::into_raw(obj);
+        unsafe {
+            (*obj).vtbl_ptr = &(*obj).vtbl;
+            Mock {
+                obj: NonNull::new_unchecked(obj),
+            }
+        }
+    }
+
+    #[inline]
+    fn object(&self) -> &MockObject<M> {
+        unsafe { self.obj.as_ref() }
+    }
+
+    /// Returns a new reference of the mock object as `T`.
+    ///
+    /// # Panics
+    /// Panics if the mock does not implement `T`.
+    pub fn com_ptr<T: Interface>(&self) -> ComPtr<T> {
+        let unknown = unsafe {
+            let p = self.obj.as_ptr() as *mut IUnknown;
+            (*p).AddRef();
+            ComPtr::from_raw(p)
+        };
+        unknown
+            .query_interface::<T>()
+            .expect("the mock does not implement the interface")
+    }
+
+    /// Returns the number of the references to the mock object.
+    #[inline]
+    pub fn ref_count(&self) -> u32 {
+        self.object().refs.get()
+    }
+
+    /// Returns the failures such as the unexpected calls and the unmet expectations.
+    pub fn failures(&self) -> Vec<String> {
+        let obj = self.object();
+        let mut failures = obj.failures.borrow().clone();
+        obj.mock.verify(&mut failures);
+        failures
+    }
+
+    /// Panics if there are failures.
+    pub fn verify(&self) {
+        let failures = self.failures();
+        if !failures.is_empty() {
+            panic!("mock verification failed:\n{}", failures.join("\n"));
+        }
+    }
+}
+
+impl<M: MockInterface> Deref for Mock<M> {
+    type Target = M;
+
+    #[inline]
+    fn deref(&self) -> &M {
+        &self.object().mock
+    }
+}
+
+impl<M: MockInterface> Drop for Mock<M> {
+    fn drop(&mut self) {
+        let refs = self.ref_count();
+        if refs == 0 {
+            let failures = self.failures();
+            unsafe { drop(Box::from_raw(self.obj.as_ptr())) };
+            if !failures.is_empty() && !std::thread::panicking() {
+                panic!("mock verification failed:\n{}", failures.join("\n"));
+            }
+        } else if !std::thread::panicking() {
+            // The object is leaked because the references are still alive.
+            panic!("{} references to the mock object were not released", refs);
+        }
+    }
+}
+
+impl<M: MockInterface> std::fmt::Debug for Mock<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Mock")
+            .field("ptr", &self.obj)
+            .field("ref_count", &self.ref_count())
+            .finish()
+    }
+}
+
+/// Declares a mock of an interface.
+///
+/// The interface and the vtable are followed by the methods in the order of the vtable.
+/// When the interface does not derive from `IUnknown` directly, the base interfaces follow
+/// after `:` up to the child of `IUnknown`.
+///
+/// `Mock::new()` creates a [`Mock`] whose fields are [`MockMethod`]s named as the methods.
+///
+/// When a method is called unexpectedly or the closure panics, the method returns the value after
+/// `=` such as `-> HRESULT = E_UNEXPECTED`, or [`MockReturn::unexpected`] when it is omitted.
+///
+/// The mock is not `Sync`, so an interface that implements [`Agile`](crate::Agile) is refused.
+///
+/// ```compile_fail
+/// use com_ptr::sys::*;
+/// use com_ptr::{com_interface, mock_com, Agile};
+///
+/// com_interface! {
+///     #[uuid(0x6b0d26a1, 0x2a33, 0x4e5c, 0x9b, 0x3f, 0x41, 0x6c, 0x8d, 0x0e, 0x7a, 0x52)]
+///     interface IValue(IValueVtbl): IUnknown(IUnknownVtbl) {
+///         fn Get() -> u32,
+///     }
+/// }
+///
+/// unsafe impl Agile for IValue {}
+///
+/// mock_com! {
+///     struct MockValue: IValue(IValueVtbl) {
+///         fn Get() -> u32,
+///     }
+/// }
+/// ```
+///
+/// ```
+/// # #[cfg(windows)]
+/// # mod example {
+/// use winapi::um::objidlbase::*;
+/// use winapi::shared::minwindef::*;
+/// use winapi::shared::ntdef::*;
+/// use winapi::um::winnt::*;
+/// use winapi::shared::wtypesbase::*;
+/// use winapi::shared::winerror::E_UNEXPECTED;
+/// use com_ptr::mock_com;
+///
+/// mock_com! {
+///     pub struct MockStream: IStream(IStreamVtbl) {
+///         fn Seek(dlibMove: LARGE_INTEGER, dwOrigin: DWORD, plibNewPosition: *mut ULARGE_INTEGER) -> HRESULT = E_UNEXPECTED,
+///         fn SetSize(libNewSize: ULARGE_INTEGER) -> HRESULT = E_UNEXPECTED,
+///         fn CopyTo(pstm: *mut IStream, cb: ULARGE_INTEGER, pcbRead: *mut ULARGE_INTEGER, pcbWritten: *mut ULARGE_INTEGER) -> HRESULT,
+///         fn Commit(grfCommitFlags: DWORD) -> HRESULT,
+///         fn Revert() -> HRESULT,
+///         fn LockRegion(libOffset: ULARGE_INTEGER, cb: ULARGE_INTEGER, dwLockType: DWORD) -> HRESULT,
+///         fn UnlockRegion(libOffset: ULARGE_INTEGER, cb: ULARGE_INTEGER, dwLockType: DWORD) -> HRESULT,
+///         fn Stat(pstatstg: *mut STATSTG, grfStatFlag: DWORD) -> HRESULT,
+///         fn Clone(ppstm: *mut *mut IStream) -> HRESULT,
+///     }: ISequentialStream(ISequentialStreamVtbl) {
+///         fn Read(pv: *mut c_void, cb: ULONG, pcbRead: *mut ULONG) -> HRESULT,
+///         fn Write(pv: *const c_void, cb: ULONG, pcbWritten: *mut ULONG) -> HRESULT,
+///     }
+/// }
+/// # }
+/// ```
+#[macro_export]
+macro_rules! mock_com {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $mock:ident: $(
+            $interface:ident($vtbl:ident) {
+                $(fn $method:ident($($p:ident: $t:ty),* $(,)?) -> $rtr:ty $(= $unexpected:expr)?,)*
+            }
+        ):+
+    ) => {
+        $(#[$attr])*
+        #[allow(non_snake_case, clippy::unused_unit)]
+        #[derive(Default, Debug)]
+        $vis struct $mock {
+            $($(
+                pub $method: $crate::mock::MockMethod<dyn FnMut($($t),*) -> $rtr>,
+            )*)+
+        }
+
+        impl $mock {
+            /// Creates a mock object.
+            #[allow(dead_code)]
+            pub fn new() -> $crate::mock::Mock<$mock> {
+                $crate::mock::Mock::new(<$mock as ::std::default::Default>::default())
+            }
+        }
+
+        const _: fn() = || {
+            #[allow(unused_imports)]
+            use $crate::class::{AgileClass, NonAgileClass};
+            $((&$crate::class::AgileCheck::<$interface, $mock>::NEW).check();)+
+        };
+
+        unsafe impl $crate::mock::MockInterface for $mock {
+            type Vtable = $crate::mock_com!(@first $($vtbl)+);
+
+            fn vtable() -> Self::Vtable {
+                $crate::mock_com!(@vtbl $mock; $($interface($vtbl) {
+                    $(fn $method($($p: $t),*) -> $rtr $(= $unexpected)?,)*
+                })+)
+            }
+
+            fn is_iid(iid: &$crate::sys::GUID) -> bool {
+                $crate::sys::IsEqualGUID(iid, &<$crate::sys::IUnknown as $crate::sys::Interface>::uuidof())
+                    $(|| $crate::sys::IsEqualGUID(iid, &<$interface as $crate::sys::Interface>::uuidof()))+
+            }
+
+            fn verify(&self, failures: &mut ::std::vec::Vec<::std::string::String>) {
+                $($(
+                    self.$method.verify(concat!(stringify!($interface), "::", stringify!($method)), failures);
+                )*)+
+            }
+        }
+    };
+    (@first $first:ident $($rest:ident)*) => {
+        $first
+    };
+    (@unexpected $rtr:ty;) => {
+        <$rtr as $crate::mock::MockReturn>::unexpected
+    };
+    (@unexpected $rtr:ty; $unexpected:expr) => {
+        || $unexpected
+    };
+    (@vtbl $mock:ident;) => {
+        $crate::sys::IUnknownVtbl {
+            QueryInterface: $crate::mock::query_interface::<$mock>,
+            AddRef: $crate::mock::add_ref::<$mock>,
+            Release: $crate::mock::release::<$mock>,
+        }
+    };
+    (@vtbl $mock:ident;
+        $interface:ident($vtbl:ident) {
+            $(fn $method:ident($($p:ident: $t:ty),*) -> $rtr:ty $(= $unexpected:expr)?,)*
+        }
+        $($rest:tt)*
+    ) => {{
+        $(
+            #[allow(non_snake_case, clippy::unused_unit)]
+            unsafe extern "system" fn $method(This: *mut $interface, $($p: $t),*) -> $rtr {
+                $crate::mock::call(
+                    This,
+                    concat!(stringify!($interface), "::", stringify!($method)),
+                    |mock: &$mock| &mock.$method,
+                    |f| f($($p),*),
+                    $crate::mock_com!(@unexpected $rtr; $($unexpected)?),
+                )
+            }
+        )*
+        $vtbl {
+            parent: $crate::mock_com!(@vtbl $mock; $($rest)*),
+            $($method,)*
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HResult;
+
+    com_interface! {
+        #[uuid(0x9c2e4a17, 0x3b6d, 0x4e81, 0xa5, 0x0f, 0x72, 0xd8, 0x1c, 0x64, 0xe9, 0x30)]
+        interface ISize(ISizeVtbl): IUnknown(IUnknownVtbl) {
+            fn GetSize(width: *mut u32, height: *mut u32) -> HRESULT,
+            fn Scale(factor: u32) -> u32,
+            fn IsEmpty() -> i32,
+        }
+    }
+
+    com_interface! {
+        #[uuid(0x9c2e4a17, 0x3b6d, 0x4e81, 0xa5, 0x0f, 0x72, 0xd8, 0x1c, 0x64, 0xe9, 0x31)]
+        interface INamedSize(INamedSizeVtbl): ISize(ISizeVtbl) {
+            fn Reset() -> (),
+        }
+    }
+
+    mock_com! {
+        struct MockSize: ISize(ISizeVtbl) {
+            fn GetSize(width: *mut u32, height: *mut u32) -> HRESULT = E_UNEXPECTED,
+            fn Scale(factor: u32) -> u32,
+            fn IsEmpty() -> i32,
+        }
+    }
+
+    mock_com! {
+        /// A mock of the derived interface.
+        struct MockNamedSize: INamedSize(INamedSizeVtbl) {
+            fn Reset() -> (),
+        }: ISize(ISizeVtbl) {
+            fn GetSize(width: *mut u32, height: *mut u32) -> HRESULT = E_UNEXPECTED,
+            fn Scale(factor: u32) -> u32,
+            fn IsEmpty() -> i32,
+        }
+    }
+
+    fn area(p: &ComPtr<ISize>) -> Result<u32, HResult> {
+        let (mut width, mut height) = (0, 0);
+        crate::hresult((), unsafe { p.GetSize(&mut width, &mut height) })?;
+        Ok(width * height)
+    }
+
+    #[test]
+    fn mock_test() {
+        let mock = MockSize::new();
+        mock.GetSize.times(2).returning(Box::new(|width, height| unsafe {
+            *width = 3;
+            *height = 4;
+            S_OK
+        }));
+        mock.Scale.returning(Box::new(|factor| factor * 2));
+        let p = mock.com_ptr::<ISize>();
+        assert_eq!(mock.ref_count(), 1);
+        assert_eq!(area(&p), Ok(12));
+        assert_eq!(area(&p.clone()), Ok(12));
+        assert_eq!(unsafe { p.Scale(5) }, 10);
+        assert_eq!(mock.Scale.calls(), 1);
+        assert!(p.query_interface::<INamedSize>().is_err());
+        drop(p);
+        assert!(mock.failures().is_empty());
+    }
+
+    #[test]
+    fn derived_test() {
+        let mock = MockNamedSize::new();
+        let count = std::rc::Rc::new(Cell::new(0));
+        let c = count.clone();
+        mock.Reset.returning(Box::new(move || c.set(c.get() + 1)));
+        mock.GetSize.returning(Box::new(|_, _| E_INVALIDARG));
+        let p = mock.com_ptr::<INamedSize>();
+        unsafe { p.Reset() };
+        assert_eq!(count.get(), 1);
+        let size: ComPtr<ISize> = p.clone().upcast();
+        assert_eq!(area(&size), Err(HResult(E_INVALIDARG)));
+        assert!(size.is_same_object(&p));
+    }
+
+    #[test]
+    fn failure_test() {
+        let mock = MockSize::new();
+        mock.GetSize.times(1);
+        mock.Scale.returning(Box::new(|_| panic!("scale")));
+        let p = mock.com_ptr::<ISize>();
+        assert_eq!(area(&p), Err(HResult::E_UNEXPECTED));
+        assert_eq!(unsafe { p.Scale(1) }, 0);
+        assert_eq!(unsafe { p.IsEmpty() }, 0);
+        drop(p);
+        assert_eq!(
+            mock.failures(),
+            vec![
+                "ISize::GetSize was called unexpectedly",
+                "ISize::Scale panicked",
+                "ISize::IsEmpty was called unexpectedly"
+            ]
+        );
+        mock.GetSize.returning(Box::new(|_, _| S_OK));
+        mock.GetSize.times(3);
+        let failures = mock.failures();
+        assert_eq!(
+            failures[3],
+            "ISize::GetSize was expected to be called 3 times but was called 1 times"
+        );
+        let result = catch_unwind(AssertUnwindSafe(|| drop(mock)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "track-refs")]
+    fn track_test() {
+        use crate::tracker::{Checkpoint, RefKind};
+
+        let mock = MockSize::new();
+        let checkpoint = Checkpoint::new();
+        let p = mock.com_ptr::<ISize>();
+        let leaks = checkpoint.leaks();
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks.refs()[0].kind(), RefKind::New);
+        assert_eq!(leaks.refs()[0].iid(), crate::Guid::from_guid(&ISize::uuidof()));
+        drop(p);
+        checkpoint.assert_no_leaks();
+    }
+
+    #[test]
+    fn leak_test() {
+        let mock = MockSize::new();
+        let p = mock.com_ptr::<ISize>();
+        let result = catch_unwind(AssertUnwindSafe(|| drop(mock)));
+        assert!(result.is_err());
+        std::mem::forget(p);
+    }
+}
@@ -0,0 +1,248 @@
+//! Activation of classes in the process.
+//!
+//! [`co_create_instance`](crate::co_create_instance) and [`ComApartment`](crate::ComApartment)
+//! use the COM runtime on Windows. With [`Backend::Emulated`], which is the only backend on other
+//! platforms, classes are activated from the factories registered by [`register_class`] and the
+//! apartments are emulated in the process.
+
+use crate::sys::*;
+use crate::{ComApartment, ComPtr, Guid, HResult};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The backends of the activation of classes and the apartments.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Backend {
+    /// The COM runtime of Windows.
+    Com,
+    /// The classes registered by [`register_class`] and the apartments emulated in the process.
+    Emulated,
+}
+
+#[cfg(windows)]
+thread_local! {
+    static EMULATED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+impl Backend {
+    /// Returns the backend of the current thread.
+    ///
+    /// The default is `Backend::Com` on Windows. `Backend::Emulated` is the only backend on
+    /// other platforms.
+    #[inline]
+    pub fn current() -> Backend {
+        #[cfg(windows)]
+        {
+            if !EMULATED.with(|emulated| emulated.get()) {
+                return Backend::Com;
+            }
+        }
+        Backend::Emulated
+    }
+
+    /// Selects the backend of the current thread.
+    ///
+    /// The apartments that are already initialized keep their backend.
+    #[cfg(windows)]
+    #[inline]
+    pub fn select(backend: Backend) {
+        EMULATED.with(|emulated| emulated.set(backend == Backend::Emulated));
+    }
+}
+
+type Factory = Arc<dyn Fn() -> Result<ComPtr<IUnknown>, HResult> + Send + Sync>;
+
+struct Entry {
+    id: u64,
+    clsid: Guid,
+    factory: Factory,
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static CLASSES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+
+fn classes() -> std::sync::MutexGuard<'static, Vec<Entry>> {
+    CLASSES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A registration of a class. The class is revoked when this is dropped.
+#[derive(Debug)]
+pub struct ClassRegistration {
+    id: u64,
+    clsid: Guid,
+}
+
+impl ClassRegistration {
+    /// Returns the CLSID of the class.
+    #[inline]
+    pub fn clsid(&self) -> Guid {
+        self.clsid
+    }
+}
+
+impl Drop for ClassRegistration {
+    fn drop(&mut self) {
+        classes().retain(|entry| entry.id != self.id);
+    }
+}
+
+/// Registers a factory of the class for [`Backend::Emulated`].
+///
+/// `factory` is called for each activation of `clsid` in any thread. The latest registration
+/// of the same CLSID is used. The classes do not support aggregation.
+///
+/// ```
+/// # fn main() -> Result<(), com_ptr::HResult> {
+/// use com_ptr::sys::*;
+/// use com_ptr::{co_create_instance, com_class, com_impl, guid, register_class, ComApartment, ComPtr};
+/// # #[cfg(windows)]
+/// # com_ptr::Backend::select(com_ptr::Backend::Emulated);
+///
+/// #[com_class(IUnknown)]
+/// struct Object;
+///
+/// let clsid = guid!("0f5cc3a0-2e5a-4b4f-8d3c-6a3f1d0e9b21");
+/// let _registration = register_class(&clsid.into(), || Ok(Object.into_com_ptr()));
+/// let _apartment = ComApartment::mta().unwrap();
+/// let p = co_create_instance::<IUnknown>(&clsid.into(), None, 1)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_class<F>(clsid: &GUID, factory: F) -> ClassRegistration
+where
+    F: Fn() -> Result<ComPtr<IUnknown>, HResult> + Send + Sync + 'static,
+{
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let clsid = Guid::from_guid(clsid);
+    classes().push(Entry {
+        id,
+        clsid,
+        factory: Arc::new(factory),
+    });
+    ClassRegistration { id, clsid }
+}
+
+/// Creates an object of the registered class like `CoCreateInstance`.
+pub(crate) fn create_instance<T: Interface>(
+    clsid: REFCLSID,
+    outer: Option<*mut IUnknown>,
+) -> Result<ComPtr<T>, HResult> {
+    if clsid.is_null() {
+        return Err(HResult::E_INVALIDARG);
+    }
+    if ComApartment::current().is_none() {
+        return Err(HResult::CO_E_NOTINITIALIZED);
+    }
+    let clsid = Guid::from_guid(unsafe { &*clsid });
+    let factory = classes()
+        .iter()
+        .rev()
+        .find(|entry| entry.clsid == clsid)
+        .map(|entry| entry.factory.clone())
+        .ok_or(HResult::REGDB_E_CLASSNOTREG)?;
+    if outer.is_some_and(|p| !p.is_null()) {
+        return Err(HResult::CLASS_E_NOAGGREGATION);
+    }
+    factory()?.query_interface::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{co_create_instance, com_class, com_impl, guid};
+    use std::sync::atomic::AtomicU32;
+
+    com_interface! {
+        #[uuid(0x4d6a1b38, 0x92e7, 0x4c05, 0xb8, 0x1f, 0x6e, 0x20, 0xa4, 0x37, 0xd9, 0x5c)]
+        interface ICounter(ICounterVtbl): IUnknown(IUnknownVtbl) {
+            fn Increment() -> u32,
+        }
+    }
+
+    #[com_class(ICounter)]
+    struct Counter(AtomicU32);
+
+    #[com_impl(ICounter)]
+    #[allow(non_snake_case)]
+    impl Counter {
+        fn Increment(&self) -> u32 {
+            self.0.fetch_add(1, Ordering::Relaxed) + 1
+        }
+    }
+
+    const CLSID_COUNTER: Guid = guid!("4d6a1b38-92e7-4c05-b81f-6e20a437d9c0");
+
+    fn in_apartment(f: impl FnOnce() + Send + 'static) {
+        std::thread::spawn(move || {
+            #[cfg(windows)]
+            Backend::select(Backend::Emulated);
+            let _apartment = ComApartment::mta().unwrap();
+            f();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn create_instance_test() {
+        in_apartment(|| {
+            let clsid = CLSID_COUNTER.to_guid();
+            let registration = register_class(&clsid, || {
+                Ok(Counter(AtomicU32::new(10)).into_com_ptr().upcast())
+            });
+            assert_eq!(registration.clsid(), CLSID_COUNTER);
+            let p = co_create_instance::<ICounter>(&clsid, None, 0).unwrap();
+            assert_eq!(unsafe { p.Increment() }, 11);
+            let q = co_create_instance::<ICounter>(&clsid, None, 0).unwrap();
+            assert!(!p.is_same_object(&q));
+            drop(registration);
+            assert_eq!(
+                co_create_instance::<ICounter>(&clsid, None, 0).err(),
+                Some(HResult::REGDB_E_CLASSNOTREG)
+            );
+        });
+    }
+
+    #[test]
+    fn error_test() {
+        in_apartment(|| {
+            let clsid = guid!("4d6a1b38-92e7-4c05-b81f-6e20a437d9c1").to_guid();
+            let _registration = register_class(&clsid, || {
+                Ok(Counter(AtomicU32::new(0)).into_com_ptr().upcast())
+            });
+            assert_eq!(
+                co_create_instance::<ISupportErrorInfo>(&clsid, None, 0).err(),
+                Some(HResult::E_NOINTERFACE)
+            );
+            let outer = co_create_instance::<IUnknown>(&clsid, None, 0).unwrap();
+            assert_eq!(
+                co_create_instance::<IUnknown>(&clsid, Some(outer.as_ptr()), 0).err(),
+                Some(HResult::CLASS_E_NOAGGREGATION)
+            );
+            let failing = guid!("4d6a1b38-92e7-4c05-b81f-6e20a437d9c2").to_guid();
+            let _failing = register_class(&failing, || Err(HResult::E_OUTOFMEMORY));
+            assert_eq!(
+                co_create_instance::<IUnknown>(&failing, None, 0).err(),
+                Some(HResult::E_OUTOFMEMORY)
+            );
+        });
+    }
+
+    #[test]
+    fn not_initialized_test() {
+        std::thread::spawn(|| {
+            #[cfg(windows)]
+            Backend::select(Backend::Emulated);
+            let clsid = guid!("4d6a1b38-92e7-4c05-b81f-6e20a437d9c3").to_guid();
+            let _registration = register_class(&clsid, || {
+                Ok(Counter(AtomicU32::new(0)).into_com_ptr().upcast())
+            });
+            assert_eq!(
+                co_create_instance::<IUnknown>(&clsid, None, 0).err(),
+                Some(HResult::CO_E_NOTINITIALIZED)
+            );
+        })
+        .join()
+        .unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+//! Win32 error codes and NTSTATUS.
+
+use crate::sys::HRESULT;
+use crate::{Facility, HResult};
+
+/// A object that wraps a Win32 error code such as the value of `GetLastError`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(transparent)]
+pub struct Win32Error(pub u32);
+
+impl Win32Error {
+    pub const ERROR_SUCCESS: Win32Error = Win32Error(0);
+    pub const ERROR_INVALID_FUNCTION: Win32Error = Win32Error(1);
+    pub const ERROR_FILE_NOT_FOUND: Win32Error = Win32Error(2);
+    pub const ERROR_PATH_NOT_FOUND: Win32Error = Win32Error(3);
+    pub const ERROR_ACCESS_DENIED: Win32Error = Win32Error(5);
+    pub const ERROR_INVALID_HANDLE: Win32Error = Win32Error(6);
+    pub const ERROR_NOT_ENOUGH_MEMORY: Win32Error = Win32Error(8);
+    pub const ERROR_INVALID_DATA: Win32Error = Win32Error(13);
+    pub const ERROR_OUTOFMEMORY: Win32Error = Win32Error(14);
+    pub const ERROR_HANDLE_EOF: Win32Error = Win32Error(38);
+    pub const ERROR_NOT_SUPPORTED: Win32Error = Win32Error(50);
+    pub const ERROR_FILE_EXISTS: Win32Error = Win32Error(80);
+    pub const ERROR_INVALID_PARAMETER: Win32Error = Win32Error(87);
+    pub const ERROR_BROKEN_PIPE: Win32Error = Win32Error(109);
+    pub const ERROR_INSUFFICIENT_BUFFER: Win32Error = Win32Error(122);
+    pub const ERROR_ALREADY_EXISTS: Win32Error = Win32Error(183);
+    pub const ERROR_TIMEOUT: Win32Error = Win32Error(1460);
+
+    /// Returns the error code of the calling thread from `GetLastError`.
+    #[cfg(windows)]
+    #[inline]
+    pub fn last() -> Win32Error {
+        Win32Error(unsafe { winapi::um::errhandlingapi::GetLastError() })
+    }
+
+    /// Returns the HRESULT like `HRESULT_FROM_WIN32`.
+    #[inline]
+    pub const fn to_hresult(self) -> HResult {
+        if self.0 as i32 <= 0 {
+            HResult(self.0 as HRESULT)
+        } else {
+            HResult(((self.0 & 0xffff) | 0x80070000) as HRESULT)
+        }
+    }
+
+    /// Returns the Win32 error code in `hr`.
+    ///
+    /// Returns `None` when `hr` is neither `S_OK` nor a failure of `Facility::Win32`.
+    #[inline]
+    pub fn from_hresult(hr: HResult) -> Option<Win32Error> {
+        if hr == HResult::S_OK {
+            Some(Win32Error::ERROR_SUCCESS)
+        } else if hr.is_failed() && hr.facility() == Facility::Win32 {
+            Some(Win32Error(hr.code_part() as u32))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn is_succeed(&self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl From<Win32Error> for HResult {
+    #[inline]
+    fn from(src: Win32Error) -> HResult {
+        src.to_hresult()
+    }
+}
+
+/// Displays the message of the HRESULT converted by `HRESULT_FROM_WIN32`.
+impl std::fmt::Display for Win32Error {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.to_hresult(), f)
+    }
+}
+
+impl std::error::Error for Win32Error {}
+
+/// The bit that marks a HRESULT mapped from NTSTATUS.
+const FACILITY_NT_BIT: u32 = 0x10000000;
+
+/// A object that wraps NTSTATUS.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(transparent)]
+pub struct NtStatus(pub i32);
+
+impl NtStatus {
+    pub const STATUS_SUCCESS: NtStatus = NtStatus(0);
+    pub const STATUS_UNSUCCESSFUL: NtStatus = NtStatus(0xC0000001u32 as i32);
+    pub const STATUS_NOT_IMPLEMENTED: NtStatus = NtStatus(0xC0000002u32 as i32);
+    pub const STATUS_INVALID_HANDLE: NtStatus = NtStatus(0xC0000008u32 as i32);
+    pub const STATUS_INVALID_PARAMETER: NtStatus = NtStatus(0xC000000Du32 as i32);
+    pub const STATUS_NO_MEMORY: NtStatus = NtStatus(0xC0000017u32 as i32);
+    pub const STATUS_ACCESS_DENIED: NtStatus = NtStatus(0xC0000022u32 as i32);
+    pub const STATUS_NOT_SUPPORTED: NtStatus = NtStatus(0xC00000BBu32 as i32);
+
+    /// Returns the HRESULT like `HRESULT_FROM_NT`.
+    #[inline]
+    pub const fn to_hresult(self) -> HResult {
+        HResult((self.0 as u32 | FACILITY_NT_BIT) as HRESULT)
+    }
+
+    /// Returns the NTSTATUS in `hr`.
+    ///
+    /// Returns `None` when `hr` was not converted by `HRESULT_FROM_NT`.
+    #[inline]
+    pub const fn from_hresult(hr: HResult) -> Option<NtStatus> {
+        if hr.0 as u32 & FACILITY_NT_BIT != 0 {
+            Some(NtStatus((hr.0 as u32 & !FACILITY_NT_BIT) as i32))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn is_succeed(&self) -> bool {
+        self.0 >= 0
+    }
+
+    #[inline]
+    pub fn is_failed(&self) -> bool {
+        self.0 < 0
+    }
+}
+
+impl From<NtStatus> for HResult {
+    #[inline]
+    fn from(src: NtStatus) -> HResult {
+        src.to_hresult()
+    }
+}
+
+impl std::fmt::Display for NtStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "NTSTATUS 0x{:08X}", self.0)
+    }
+}
+
+impl std::error::Error for NtStatus {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win32_test() {
+        let e = Win32Error::ERROR_ACCESS_DENIED;
+        assert_eq!(HResult::from(e), HResult::E_ACCESSDENIED);
+        assert_eq!(Win32Error::from_hresult(HResult::E_ACCESSDENIED), Some(e));
+        assert_eq!(Win32Error::ERROR_SUCCESS.to_hresult(), HResult::S_OK);
+        assert_eq!(
+            Win32Error::from_hresult(HResult::S_OK),
+            Some(Win32Error::ERROR_SUCCESS)
+        );
+        assert_eq!(Win32Error::from_hresult(HResult::E_NOINTERFACE), None);
+        assert_eq!(Win32Error::from_hresult(HResult::S_FALSE), None);
+        let hr = HResult(0x80070005u32 as HRESULT);
+        assert_eq!(Win32Error(0x80070005).to_hresult(), hr);
+    }
+
+    #[test]
+    fn nt_status_test() {
+        let status = NtStatus::STATUS_ACCESS_DENIED;
+        let hr = HResult::from(status);
+        assert_eq!(hr, HResult(0xD0000022u32 as HRESULT));
+        assert!(hr.is_failed());
+        assert_eq!(NtStatus::from_hresult(hr), Some(status));
+        assert_eq!(NtStatus::from_hresult(HResult::E_ACCESSDENIED), None);
+        assert_eq!(status.to_string(), "NTSTATUS 0xC0000022");
+    }
+
+    #[test]
+    fn question_mark_test() {
+        fn win32() -> Result<(), Win32Error> {
+            Err(Win32Error::ERROR_FILE_NOT_FOUND)
+        }
+        fn nt() -> Result<(), NtStatus> {
+            Err(NtStatus::STATUS_NO_MEMORY)
+        }
+        fn f() -> Result<(), HResult> {
+            win32()?;
+            Ok(())
+        }
+        fn g() -> Result<(), HResult> {
+            nt()?;
+            Ok(())
+        }
+        assert_eq!(f(), Err(HResult(0x80070002u32 as HRESULT)));
+        assert_eq!(g(), Err(HResult(0xD0000017u32 as HRESULT)));
+        let e: Box<dyn std::error::Error> = Box::new(Win32Error::ERROR_FILE_NOT_FOUND);
+        assert_eq!(e.to_string(), HResult(0x80070002u32 as HRESULT).to_string());
+    }
+}
@@ -0,0 +1,179 @@
+//! Platform-neutral definitions of the COM types used by this crate.
+//!
+//! On Windows these are the winapi types. On other targets the same layouts are defined here,
+//! so that objects implementing the `IUnknown` ABI can be used everywhere.
+//!
+//! On Windows, [`Interface`] is `winapi::Interface`, so `ComPtr` holds every winapi interface.
+//! [`ComInterface`](crate::ComInterface) and [`Inherits`](crate::Inherits) are additionally
+//! implemented for the interfaces of winapi that this crate uses, such as
+//! `IStream: ISequentialStream`. The interfaces of DXGI, Direct3D 11, Direct3D 12 and WIC opt in
+//! by the `dxgi`, `d3d11`, `d3d12` and `wincodec` features, so that `ComPtr<IDXGIFactory1>` is
+//! converted into `ComPtr<IDXGIFactory>` by `upcast` or `into`.
+#![allow(non_snake_case, non_camel_case_types, clippy::missing_safety_doc)]
+
+#[cfg(windows)]
+pub use winapi::ctypes::c_void;
+#[cfg(windows)]
+pub use winapi::shared::guiddef::{IsEqualGUID, GUID, IID, REFCLSID, REFGUID, REFIID};
+#[cfg(windows)]
+pub use winapi::shared::minwindef::{DWORD, ULONG};
+#[cfg(windows)]
+pub use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+#[cfg(windows)]
+pub use winapi::um::winnt::HRESULT;
+#[cfg(windows)]
+pub use winapi::Interface;
+
+#[cfg(not(windows))]
+pub use std::ffi::c_void;
+
+#[cfg(not(windows))]
+pub type HRESULT = i32;
+#[cfg(not(windows))]
+pub type ULONG = u32;
+#[cfg(not(windows))]
+pub type DWORD = u32;
+
+/// The layout of `GUID`.
+#[cfg(not(windows))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GUID {
+    pub Data1: u32,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8],
+}
+
+#[cfg(not(windows))]
+pub type IID = GUID;
+#[cfg(not(windows))]
+pub type REFGUID = *const GUID;
+#[cfg(not(windows))]
+pub type REFIID = *const IID;
+#[cfg(not(windows))]
+pub type REFCLSID = *const IID;
+
+#[cfg(not(windows))]
+#[inline]
+pub fn IsEqualGUID(g1: &GUID, g2: &GUID) -> bool {
+    g1.Data1 == g2.Data1 && g1.Data2 == g2.Data2 && g1.Data3 == g2.Data3 && g1.Data4 == g2.Data4
+}
+
+/// The same trait as `winapi::Interface`.
+///
+/// This trait is implemented for all [`ComInterface`](crate::ComInterface) types.
+#[cfg(not(windows))]
+pub unsafe trait Interface {
+    fn uuidof() -> GUID;
+}
+
+/// The layout of the `IUnknown` vtable.
+#[cfg(not(windows))]
+#[repr(C)]
+pub struct IUnknownVtbl {
+    pub QueryInterface: unsafe extern "system" fn(
+        This: *mut IUnknown,
+        riid: REFIID,
+        ppvObject: *mut *mut c_void,
+    ) -> HRESULT,
+    pub AddRef: unsafe extern "system" fn(This: *mut IUnknown) -> ULONG,
+    pub Release: unsafe extern "system" fn(This: *mut IUnknown) -> ULONG,
+}
+
+/// The layout of `IUnknown`.
+#[cfg(not(windows))]
+#[repr(C)]
+pub struct IUnknown {
+    pub lpVtbl: *const IUnknownVtbl,
+}
+
+#[cfg(not(windows))]
+impl IUnknown {
+    #[inline]
+    pub unsafe fn QueryInterface(&self, riid: REFIID, ppvObject: *mut *mut c_void) -> HRESULT {
+        ((*self.lpVtbl).QueryInterface)(self as *const _ as *mut _, riid, ppvObject)
+    }
+
+    #[inline]
+    pub unsafe fn AddRef(&self) -> ULONG {
+        ((*self.lpVtbl).AddRef)(self as *const _ as *mut _)
+    }
+
+    #[inline]
+    pub unsafe fn Release(&self) -> ULONG {
+        ((*self.lpVtbl).Release)(self as *const _ as *mut _)
+    }
+}
+
+#[cfg(not(windows))]
+unsafe impl<T: crate::ComInterface> Interface for T {
+    #[inline]
+    fn uuidof() -> GUID {
+        T::IID
+    }
+}
+
+unsafe impl crate::ComInterface for IUnknown {
+    type Vtable = IUnknownVtbl;
+
+    const IID: GUID = GUID {
+        Data1: 0x00000000,
+        Data2: 0x0000,
+        Data3: 0x0000,
+        Data4: [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    };
+}
+
+com_interface! {
+    #[uuid(0xdf0b3d60, 0x548f, 0x101b, 0x8e, 0x65, 0x08, 0x00, 0x2b, 0x2b, 0xd1, 0x19)]
+    /// The layout of `ISupportErrorInfo`, which winapi does not declare.
+    interface ISupportErrorInfo(ISupportErrorInfoVtbl): IUnknown(IUnknownVtbl) {
+        fn InterfaceSupportsErrorInfo(
+            riid: REFIID,
+        ) -> HRESULT,
+    }
+}
+
+/// Implements `ComInterface`, the hierarchy and the upcasts for the winapi interfaces.
+#[cfg(windows)]
+macro_rules! winapi_interfaces {
+    ($(
+        $interface:ident($vtbl:ident): $base:ident
+            = ($l:expr, $w1:expr, $w2:expr, [$($b:expr),*]),
+    )*) => {
+        $(
+            unsafe impl crate::ComInterface for $interface {
+                type Vtable = $vtbl;
+
+                const IID: crate::sys::GUID = crate::sys::GUID {
+                    Data1: $l,
+                    Data2: $w1,
+                    Data3: $w2,
+                    Data4: [$($b),*],
+                };
+            }
+
+            crate::inherits!($interface: $base);
+
+            impl<T: crate::Inherits<$interface>> From<crate::ComPtr<T>> for crate::ComPtr<$interface> {
+                #[inline]
+                fn from(src: crate::ComPtr<T>) -> crate::ComPtr<$interface> {
+                    src.upcast()
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(windows)]
+#[rustfmt::skip]
+mod winapi_interfaces;
+
+pub const S_OK: HRESULT = 0;
+pub const S_FALSE: HRESULT = 1;
+pub const E_NOINTERFACE: HRESULT = 0x80004002u32 as HRESULT;
+pub const E_INVALIDARG: HRESULT = 0x80070057u32 as HRESULT;
+pub const E_POINTER: HRESULT = 0x80004003u32 as HRESULT;
+pub const E_UNEXPECTED: HRESULT = 0x8000FFFFu32 as HRESULT;
+pub const RPC_E_CHANGED_MODE: HRESULT = 0x80010106u32 as HRESULT;
@@ -0,0 +1,429 @@
+// Generated by scripts/winapi_interfaces.py from winapi 0.3.9. Do not edit.
+
+mod unknwnbase {
+    use winapi::um::unknwnbase::*;
+
+    winapi_interfaces! {
+        AsyncIUnknown(AsyncIUnknownVtbl): IUnknown = (0x000e0000, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IClassFactory(IClassFactoryVtbl): IUnknown = (0x00000001, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+    }
+}
+
+mod objidlbase {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::objidlbase::*;
+
+    winapi_interfaces! {
+        IMarshal(IMarshalVtbl): IUnknown = (0x00000003, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        INoMarshal(INoMarshalVtbl): IUnknown = (0xecc8691b, 0xc1db, 0x4dc0, [0x85, 0x5e, 0x65, 0xf6, 0xc5, 0x51, 0xaf, 0x49]),
+        IAgileObject(IAgileObjectVtbl): IUnknown = (0x94ea2b94, 0xe9cc, 0x49e0, [0xc0, 0xff, 0xee, 0x64, 0xca, 0x8f, 0x5b, 0x90]),
+        IActivationFilter(IActivationFilterVtbl): IUnknown = (0x00000017, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IMarshal2(IMarshal2Vtbl): IMarshal = (0x000001cf, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IMalloc(IMallocVtbl): IUnknown = (0x00000002, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IStdMarshalInfo(IStdMarshalInfoVtbl): IUnknown = (0x00000018, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IExternalConnection(IExternalConnectionVtbl): IUnknown = (0x00000019, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IMultiQI(IMultiQIVtbl): IUnknown = (0x00000020, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        AsyncIMultiQI(AsyncIMultiQIVtbl): IUnknown = (0x000e0020, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IInternalUnknown(IInternalUnknownVtbl): IUnknown = (0x00000021, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IEnumUnknown(IEnumUnknownVtbl): IUnknown = (0x00000100, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IEnumString(IEnumStringVtbl): IUnknown = (0x00000101, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISequentialStream(ISequentialStreamVtbl): IUnknown = (0x0c733a30, 0x2a1c, 0x11ce, [0xad, 0xe5, 0x00, 0xaa, 0x00, 0x44, 0x77, 0x3d]),
+        IStream(IStreamVtbl): ISequentialStream = (0x0000000c, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IRpcChannelBuffer(IRpcChannelBufferVtbl): IUnknown = (0xd5f56b60, 0x593b, 0x101a, [0xb5, 0x69, 0x08, 0x00, 0x2b, 0x2d, 0xbf, 0x7a]),
+        IRpcChannelBuffer2(IRpcChannelBuffer2Vtbl): IRpcChannelBuffer = (0x594f31d0, 0x7f19, 0x11d0, [0xb1, 0x94, 0x00, 0xa0, 0xc9, 0x0d, 0xc8, 0xbf]),
+        IAsyncRpcChannelBuffer(IAsyncRpcChannelBufferVtbl): IRpcChannelBuffer2 = (0xa5029fb6, 0x3c34, 0x11d1, [0x9c, 0x99, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0xaa]),
+        IRpcChannelBuffer3(IRpcChannelBuffer3Vtbl): IRpcChannelBuffer2 = (0x25b15600, 0x0115, 0x11d0, [0xbf, 0x0d, 0x00, 0xaa, 0x00, 0xb8, 0xdf, 0xd2]),
+        IRpcSyntaxNegotiate(IRpcSyntaxNegotiateVtbl): IUnknown = (0x58a08519, 0x24c8, 0x4935, [0xb4, 0x82, 0x3f, 0xd8, 0x23, 0x33, 0x3a, 0x4f]),
+        IRpcProxyBuffer(IRpcProxyBufferVtbl): IUnknown = (0xd5f56a34, 0x593b, 0x101a, [0xb5, 0x69, 0x08, 0x00, 0x2b, 0x2d, 0xbf, 0x7a]),
+        IRpcStubBuffer(IRpcStubBufferVtbl): IUnknown = (0xd5f56afc, 0x593b, 0x101a, [0xb5, 0x69, 0x08, 0x00, 0x2b, 0x2d, 0xbf, 0x7a]),
+        IPSFactoryBuffer(IPSFactoryBufferVtbl): IUnknown = (0xd5f569d0, 0x593b, 0x101a, [0xb5, 0x69, 0x08, 0x00, 0x2b, 0x2d, 0xbf, 0x7a]),
+        IChannelHook(IChannelHookVtbl): IUnknown = (0x1008c4a0, 0x7613, 0x11cf, [0x9a, 0xf1, 0x00, 0x20, 0xaf, 0x6e, 0x72, 0xf4]),
+        IClientSecurity(IClientSecurityVtbl): IUnknown = (0x0000013d, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IServerSecurity(IServerSecurityVtbl): IUnknown = (0x0000013e, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IRpcOptions(IRpcOptionsVtbl): IUnknown = (0x00000144, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IGlobalOptions(IGlobalOptionsVtbl): IUnknown = (0x0000015b, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISurrogate(ISurrogateVtbl): IUnknown = (0x00000022, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IGlobalInterfaceTable(IGlobalInterfaceTableVtbl): IUnknown = (0x00000146, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISynchronize(ISynchronizeVtbl): IUnknown = (0x00000030, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISynchronizeHandle(ISynchronizeHandleVtbl): IUnknown = (0x00000031, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISynchronizeEvent(ISynchronizeEventVtbl): ISynchronizeHandle = (0x00000032, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISynchronizeContainer(ISynchronizeContainerVtbl): IUnknown = (0x00000033, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ISynchronizeMutex(ISynchronizeMutexVtbl): ISynchronize = (0x00000025, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ICancelMethodCalls(ICancelMethodCallsVtbl): IUnknown = (0x00000029, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IAsyncManager(IAsyncManagerVtbl): IUnknown = (0x0000002a, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ICallFactory(ICallFactoryVtbl): IUnknown = (0x1c733a30, 0x2a1c, 0x11ce, [0xad, 0xe5, 0x00, 0xaa, 0x00, 0x44, 0x77, 0x3d]),
+        IRpcHelper(IRpcHelperVtbl): IUnknown = (0x00000149, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IReleaseMarshalBuffers(IReleaseMarshalBuffersVtbl): IUnknown = (0xeb0cb9e8, 0x7996, 0x11d2, [0x87, 0x2e, 0x00, 0x00, 0xf8, 0x08, 0x08, 0x59]),
+        IWaitMultiple(IWaitMultipleVtbl): IUnknown = (0x0000002b, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IAddrTrackingControl(IAddrTrackingControlVtbl): IUnknown = (0x00000147, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IAddrExclusionControl(IAddrExclusionControlVtbl): IUnknown = (0x00000148, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IPipeByte(IPipeByteVtbl): IUnknown = (0xdb2f3aca, 0x2f86, 0x11d1, [0x8e, 0x04, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0x9a]),
+        AsyncIPipeByte(AsyncIPipeByteVtbl): IUnknown = (0xdb2f3acb, 0x2f86, 0x11d1, [0x8e, 0x04, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0x9a]),
+        IPipeLong(IPipeLongVtbl): IUnknown = (0xdb2f3acc, 0x2f86, 0x11d1, [0x8e, 0x04, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0x9a]),
+        AsyncIPipeLong(AsyncIPipeLongVtbl): IUnknown = (0xdb2f3acd, 0x2f86, 0x11d1, [0x8e, 0x04, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0x9a]),
+        IPipeDouble(IPipeDoubleVtbl): IUnknown = (0xdb2f3ace, 0x2f86, 0x11d1, [0x8e, 0x04, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0x9a]),
+        AsyncIPipeDouble(AsyncIPipeDoubleVtbl): IUnknown = (0xdb2f3acf, 0x2f86, 0x11d1, [0x8e, 0x04, 0x00, 0xc0, 0x4f, 0xb9, 0x98, 0x9a]),
+        IEnumContextProps(IEnumContextPropsVtbl): IUnknown = (0x000001c1, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IContext(IContextVtbl): IUnknown = (0x000001c0, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IObjContext(IObjContextVtbl): IContext = (0x000001c6, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IComThreadingInfo(IComThreadingInfoVtbl): IUnknown = (0x000001ce, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IProcessInitControl(IProcessInitControlVtbl): IUnknown = (0x72380d55, 0x8d2b, 0x43a3, [0x85, 0x13, 0x2b, 0x6e, 0xf3, 0x14, 0x34, 0xe9]),
+        IFastRundown(IFastRundownVtbl): IUnknown = (0x00000040, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IMarshalingStream(IMarshalingStreamVtbl): IStream = (0xd8f2f5e6, 0x6102, 0x4863, [0x9f, 0x26, 0x38, 0x9a, 0x46, 0x76, 0xef, 0xde]),
+        IAgileReference(IAgileReferenceVtbl): IUnknown = (0xc03f6a43, 0x65a4, 0x9818, [0x98, 0x7e, 0xe0, 0xb8, 0x10, 0xd2, 0xa6, 0xf2]),
+    }
+}
+
+mod oaidl {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::oaidl::*;
+
+    winapi_interfaces! {
+        ICreateTypeInfo(ICreateTypeInfoVtbl): IUnknown = (0x00020405, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IDispatch(IDispatchVtbl): IUnknown = (0x00020400, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IRecordInfo(IRecordInfoVtbl): IUnknown = (0x0000002F, 0x0000, 0x0000, [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ITypeComp(ITypeCompVtbl): IUnknown = (0x00020403, 0x0000, 0x0000, [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ITypeLib(ITypeLibVtbl): IUnknown = (0x00020402, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        ITypeInfo(ITypeInfoVtbl): IUnknown = (0x00020401, 0x0000, 0x0000, [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46]),
+        IErrorInfo(IErrorInfoVtbl): IUnknown = (0x1cf2b120, 0x547d, 0x101b, [0x8e, 0x65, 0x08, 0x00, 0x2b, 0x2b, 0xd1, 0x19]),
+        ICreateErrorInfo(ICreateErrorInfoVtbl): IUnknown = (0x22f03340, 0x547d, 0x101b, [0x8e, 0x65, 0x08, 0x00, 0x2b, 0x2b, 0xd1, 0x19]),
+        IErrorLog(IErrorLogVtbl): IUnknown = (0x3127ca40, 0x446e, 0x11ce, [0x81, 0x35, 0x00, 0xaa, 0x00, 0x4b, 0xb8, 0x51]),
+    }
+}
+
+#[cfg(feature = "dxgi")]
+mod dxgi {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::shared::dxgi::*;
+
+    winapi_interfaces! {
+        IDXGIObject(IDXGIObjectVtbl): IUnknown = (0xaec22fb8, 0x76f3, 0x4639, [0x9b, 0xe0, 0x28, 0xeb, 0x43, 0xa6, 0x7a, 0x2e]),
+        IDXGIDeviceSubObject(IDXGIDeviceSubObjectVtbl): IDXGIObject = (0x3d3e0379, 0xf9de, 0x4d58, [0xbb, 0x6c, 0x18, 0xd6, 0x29, 0x92, 0xf1, 0xa6]),
+        IDXGIResource(IDXGIResourceVtbl): IDXGIDeviceSubObject = (0x035f3ab4, 0x482e, 0x4e50, [0xb4, 0x1f, 0x8a, 0x7f, 0x8b, 0xd8, 0x96, 0x0b]),
+        IDXGIKeyedMutex(IDXGIKeyedMutexVtbl): IDXGIDeviceSubObject = (0x9d8e1289, 0xd7b3, 0x465f, [0x81, 0x26, 0x25, 0x0e, 0x34, 0x9a, 0xf8, 0x5d]),
+        IDXGISurface(IDXGISurfaceVtbl): IDXGIDeviceSubObject = (0xcafcb56c, 0x6ac3, 0x4889, [0xbf, 0x47, 0x9e, 0x23, 0xbb, 0xd2, 0x60, 0xec]),
+        IDXGISurface1(IDXGISurface1Vtbl): IDXGISurface = (0x4ae63092, 0x6327, 0x4c1b, [0x80, 0xae, 0xbf, 0xe1, 0x2e, 0xa3, 0x2b, 0x86]),
+        IDXGIAdapter(IDXGIAdapterVtbl): IDXGIObject = (0x2411e7e1, 0x12ac, 0x4ccf, [0xbd, 0x14, 0x97, 0x98, 0xe8, 0x53, 0x4d, 0xc0]),
+        IDXGIOutput(IDXGIOutputVtbl): IDXGIObject = (0xae02eedb, 0xc735, 0x4690, [0x8d, 0x52, 0x5a, 0x8d, 0xc2, 0x02, 0x13, 0xaa]),
+        IDXGISwapChain(IDXGISwapChainVtbl): IDXGIDeviceSubObject = (0x310d36a0, 0xd2e7, 0x4c0a, [0xaa, 0x04, 0x6a, 0x9d, 0x23, 0xb8, 0x88, 0x6a]),
+        IDXGIFactory(IDXGIFactoryVtbl): IDXGIObject = (0x7b7166ec, 0x21c7, 0x44ae, [0xb2, 0x1a, 0xc9, 0xae, 0x32, 0x1a, 0xe3, 0x69]),
+        IDXGIDevice(IDXGIDeviceVtbl): IDXGIObject = (0x54ec77fa, 0x1377, 0x44e6, [0x8c, 0x32, 0x88, 0xfd, 0x5f, 0x44, 0xc8, 0x4c]),
+        IDXGIFactory1(IDXGIFactory1Vtbl): IDXGIFactory = (0x770aae78, 0xf26f, 0x4dba, [0xa8, 0x29, 0x25, 0x3c, 0x83, 0xd1, 0xb3, 0x87]),
+        IDXGIAdapter1(IDXGIAdapter1Vtbl): IDXGIAdapter = (0x29038f61, 0x3839, 0x4626, [0x91, 0xfd, 0x08, 0x68, 0x79, 0x01, 0x1a, 0x05]),
+        IDXGIDevice1(IDXGIDevice1Vtbl): IDXGIDevice = (0x77db970f, 0x6276, 0x48ba, [0xba, 0x28, 0x07, 0x01, 0x43, 0xb4, 0x39, 0x2c]),
+    }
+}
+
+#[cfg(feature = "dxgi")]
+mod dxgi1_2 {
+    use winapi::shared::dxgi::{IDXGIAdapter1, IDXGIDevice1, IDXGIFactory1, IDXGIObject, IDXGIOutput, IDXGIResource, IDXGISurface1, IDXGISwapChain};
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::shared::dxgi1_2::*;
+
+    winapi_interfaces! {
+        IDXGIAdapter2(IDXGIAdapter2Vtbl): IDXGIAdapter1 = (0x0aa1ae0a, 0xfa0e, 0x4b84, [0x86, 0x44, 0xe0, 0x5f, 0xf8, 0xe5, 0xac, 0xb5]),
+        IDXGIDevice2(IDXGIDevice2Vtbl): IDXGIDevice1 = (0x05008617, 0xfbfd, 0x4051, [0xa7, 0x90, 0x14, 0x48, 0x84, 0xb4, 0xf6, 0xa9]),
+        IDXGIDisplayControl(IDXGIDisplayControlVtbl): IUnknown = (0xea9dbf1a, 0xc88e, 0x4486, [0x85, 0x4a, 0x98, 0xaa, 0x01, 0x38, 0xf3, 0x0c]),
+        IDXGIFactory2(IDXGIFactory2Vtbl): IDXGIFactory1 = (0x50c83a1c, 0xe072, 0x4c48, [0x87, 0xb0, 0x36, 0x30, 0xfa, 0x36, 0xa6, 0xd0]),
+        IDXGIOutput1(IDXGIOutput1Vtbl): IDXGIOutput = (0x00cddea8, 0x939b, 0x4b83, [0xa3, 0x40, 0xa6, 0x85, 0x22, 0x66, 0x66, 0xcc]),
+        IDXGIOutputDuplication(IDXGIOutputDuplicationVtbl): IDXGIObject = (0x191cfac3, 0xa341, 0x470d, [0xb2, 0x6e, 0xa8, 0x64, 0xf4, 0x28, 0x31, 0x9c]),
+        IDXGIResource1(IDXGIResource1Vtbl): IDXGIResource = (0x30961379, 0x4609, 0x4a41, [0x99, 0x8e, 0x54, 0xfe, 0x56, 0x7e, 0xe0, 0xc1]),
+        IDXGISurface2(IDXGISurface2Vtbl): IDXGISurface1 = (0xaba496dd, 0xb617, 0x4cb8, [0xa8, 0x66, 0xbc, 0x44, 0xd7, 0xeb, 0x1f, 0xa2]),
+        IDXGISwapChain1(IDXGISwapChain1Vtbl): IDXGISwapChain = (0x790a45f7, 0x0d42, 0x4876, [0x98, 0x3a, 0x0a, 0x55, 0xcf, 0xe6, 0xf4, 0xaa]),
+    }
+}
+
+#[cfg(feature = "dxgi")]
+mod dxgi1_3 {
+    use winapi::shared::dxgi1_2::{IDXGIDevice2, IDXGIFactory2, IDXGIOutput1, IDXGISwapChain1};
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::shared::dxgi1_3::*;
+
+    winapi_interfaces! {
+        IDXGIDecodeSwapChain(IDXGIDecodeSwapChainVtbl): IUnknown = (0x2633066b, 0x4514, 0x4c7a, [0x8f, 0xd8, 0x12, 0xea, 0x98, 0x05, 0x9d, 0x18]),
+        IDXGIDevice3(IDXGIDevice3Vtbl): IDXGIDevice2 = (0x6007896c, 0x3244, 0x4afd, [0xbf, 0x18, 0xa6, 0xd3, 0xbe, 0xda, 0x50, 0x23]),
+        IDXGIFactory3(IDXGIFactory3Vtbl): IDXGIFactory2 = (0x25483823, 0xcd46, 0x4c7d, [0x86, 0xca, 0x47, 0xaa, 0x95, 0xb8, 0x37, 0xbd]),
+        IDXGIFactoryMedia(IDXGIFactoryMediaVtbl): IUnknown = (0x41e7d1f2, 0xa591, 0x4f7b, [0xa2, 0xe5, 0xfa, 0x9c, 0x84, 0x3e, 0x1c, 0x12]),
+        IDXGIOutput2(IDXGIOutput2Vtbl): IDXGIOutput1 = (0x595e39d1, 0x2724, 0x4663, [0x99, 0xb1, 0xda, 0x96, 0x9d, 0xe2, 0x83, 0x64]),
+        IDXGIOutput3(IDXGIOutput3Vtbl): IDXGIOutput2 = (0x8a6bb301, 0x7e7e, 0x41f4, [0xa8, 0xe0, 0x5b, 0x32, 0xf7, 0xf9, 0x9b, 0x18]),
+        IDXGISwapChain2(IDXGISwapChain2Vtbl): IDXGISwapChain1 = (0xa8be2ac4, 0x199f, 0x4946, [0xb3, 0x31, 0x79, 0x59, 0x9f, 0xb9, 0x8d, 0xe7]),
+        IDXGISwapChainMedia(IDXGISwapChainMediaVtbl): IUnknown = (0xdd95b90b, 0xf05f, 0x4f6a, [0xbd, 0x65, 0x25, 0xbf, 0xb2, 0x64, 0xbd, 0x84]),
+    }
+}
+
+#[cfg(feature = "dxgi")]
+mod dxgi1_4 {
+    use winapi::shared::dxgi1_2::IDXGIAdapter2;
+    use winapi::shared::dxgi1_3::{IDXGIFactory3, IDXGIOutput3, IDXGISwapChain2};
+    use winapi::shared::dxgi1_4::*;
+
+    winapi_interfaces! {
+        IDXGIAdapter3(IDXGIAdapter3Vtbl): IDXGIAdapter2 = (0x645967a4, 0x1392, 0x4310, [0xa7, 0x98, 0x80, 0x53, 0xce, 0x3e, 0x93, 0xfd]),
+        IDXGIFactory4(IDXGIFactory4Vtbl): IDXGIFactory3 = (0x1bc6ea02, 0xef36, 0x464f, [0xbf, 0x0c, 0x21, 0xca, 0x39, 0xe5, 0x16, 0x8a]),
+        IDXGIOutput4(IDXGIOutput4Vtbl): IDXGIOutput3 = (0xdc7dca35, 0x2196, 0x414d, [0x9f, 0x53, 0x61, 0x78, 0x84, 0x03, 0x2a, 0x60]),
+        IDXGISwapChain3(IDXGISwapChain3Vtbl): IDXGISwapChain2 = (0x94d99bdb, 0xf1f8, 0x4ab0, [0xb2, 0x36, 0x7d, 0xa0, 0x17, 0x0e, 0xda, 0xb1]),
+    }
+}
+
+#[cfg(feature = "dxgi")]
+mod dxgi1_5 {
+    use winapi::shared::dxgi1_3::IDXGIDevice3;
+    use winapi::shared::dxgi1_4::{IDXGIFactory4, IDXGIOutput4, IDXGISwapChain3};
+    use winapi::shared::dxgi1_5::*;
+
+    winapi_interfaces! {
+        IDXGIOutput5(IDXGIOutput5Vtbl): IDXGIOutput4 = (0x80a07424, 0xab52, 0x42eb, [0x83, 0x3c, 0x0c, 0x42, 0xfd, 0x28, 0x2d, 0x98]),
+        IDXGISwapChain4(IDXGISwapChain4Vtbl): IDXGISwapChain3 = (0x3d585d5a, 0xbd4a, 0x489e, [0xb1, 0xf4, 0x3d, 0xbc, 0xb6, 0x45, 0x2f, 0xfb]),
+        IDXGIDevice4(IDXGIDevice4Vtbl): IDXGIDevice3 = (0x95b4f95f, 0xd8da, 0x4ca4, [0x9e, 0xe6, 0x3b, 0x76, 0xd5, 0x96, 0x8a, 0x10]),
+        IDXGIFactory5(IDXGIFactory5Vtbl): IDXGIFactory4 = (0x7632e1f5, 0xee65, 0x4dca, [0x87, 0xfd, 0x84, 0xcd, 0x75, 0xf8, 0x83, 0x8d]),
+    }
+}
+
+#[cfg(feature = "dxgi")]
+mod dxgi1_6 {
+    use winapi::shared::dxgi1_4::IDXGIAdapter3;
+    use winapi::shared::dxgi1_5::{IDXGIFactory5, IDXGIOutput5};
+    use winapi::shared::dxgi1_6::*;
+
+    winapi_interfaces! {
+        IDXGIAdapter4(IDXGIAdapter4Vtbl): IDXGIAdapter3 = (0x3c8d99d1, 0x4fbf, 0x4181, [0xa8, 0x2c, 0xaf, 0x66, 0xbf, 0x7b, 0xd2, 0x4e]),
+        IDXGIOutput6(IDXGIOutput6Vtbl): IDXGIOutput5 = (0x068346e8, 0xaaec, 0x4b84, [0xad, 0xd7, 0x13, 0x7f, 0x51, 0x3f, 0x77, 0xa1]),
+        IDXGIFactory6(IDXGIFactory6Vtbl): IDXGIFactory5 = (0xc1b6694f, 0xff09, 0x44a9, [0xb0, 0x3c, 0x77, 0x90, 0x0a, 0x0a, 0x1d, 0x17]),
+    }
+}
+
+#[cfg(any(feature = "d3d11", feature = "d3d12"))]
+mod d3dcommon {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3dcommon::*;
+
+    winapi_interfaces! {
+        ID3D10Blob(ID3D10BlobVtbl): IUnknown = (0x8ba5fb08, 0x5195, 0x40e2, [0xac, 0x58, 0xd, 0x98, 0x9c, 0x3a, 0x1, 0x2]),
+    }
+}
+
+#[cfg(feature = "d3d11")]
+mod d3d11 {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d11::*;
+
+    winapi_interfaces! {
+        ID3D11DeviceChild(ID3D11DeviceChildVtbl): IUnknown = (0x1841e5c8, 0x16b0, 0x489b, [0xbc, 0xc8, 0x44, 0xcf, 0xb0, 0xd5, 0xde, 0xae]),
+        ID3D11DepthStencilState(ID3D11DepthStencilStateVtbl): ID3D11DeviceChild = (0x03823efb, 0x8d8f, 0x4e1c, [0x9a, 0xa2, 0xf6, 0x4b, 0xb2, 0xcb, 0xfd, 0xf1]),
+        ID3D11BlendState(ID3D11BlendStateVtbl): ID3D11DeviceChild = (0x75b68faa, 0x347d, 0x4159, [0x8f, 0x45, 0xa0, 0x64, 0x0f, 0x01, 0xcd, 0x9a]),
+        ID3D11RasterizerState(ID3D11RasterizerStateVtbl): ID3D11DeviceChild = (0x9bb4ab81, 0xab1a, 0x4d8f, [0xb5, 0x06, 0xfc, 0x04, 0x20, 0x0b, 0x6e, 0xe7]),
+        ID3D11Resource(ID3D11ResourceVtbl): ID3D11DeviceChild = (0xdc8e63f3, 0xd12b, 0x4952, [0xb4, 0x7b, 0x5e, 0x45, 0x02, 0x6a, 0x86, 0x2d]),
+        ID3D11Buffer(ID3D11BufferVtbl): ID3D11Resource = (0x48570b85, 0xd1ee, 0x4fcd, [0xa2, 0x50, 0xeb, 0x35, 0x07, 0x22, 0xb0, 0x37]),
+        ID3D11Texture1D(ID3D11Texture1DVtbl): ID3D11Resource = (0xf8fb5c27, 0xc6b3, 0x4f75, [0xa4, 0xc8, 0x43, 0x9a, 0xf2, 0xef, 0x56, 0x4c]),
+        ID3D11Texture2D(ID3D11Texture2DVtbl): ID3D11Resource = (0x6f15aaf2, 0xd208, 0x4e89, [0x9a, 0xb4, 0x48, 0x95, 0x35, 0xd3, 0x4f, 0x9c]),
+        ID3D11Texture3D(ID3D11Texture3DVtbl): ID3D11Resource = (0x037e866e, 0xf56d, 0x4357, [0xa8, 0xaf, 0x9d, 0xab, 0xbe, 0x6e, 0x25, 0x0e]),
+        ID3D11View(ID3D11ViewVtbl): ID3D11DeviceChild = (0x839d1216, 0xbb2e, 0x412b, [0xb7, 0xf4, 0xa9, 0xdb, 0xeb, 0xe0, 0x8e, 0xd1]),
+        ID3D11ShaderResourceView(ID3D11ShaderResourceViewVtbl): ID3D11View = (0xb0e06fe0, 0x8192, 0x4e1a, [0xb1, 0xca, 0x36, 0xd7, 0x41, 0x47, 0x10, 0xb2]),
+        ID3D11RenderTargetView(ID3D11RenderTargetViewVtbl): ID3D11View = (0xdfdba067, 0x0b8d, 0x4865, [0x87, 0x5b, 0xd7, 0xb4, 0x51, 0x6c, 0xc1, 0x64]),
+        ID3D11DepthStencilView(ID3D11DepthStencilViewVtbl): ID3D11View = (0x9fdac92a, 0x1876, 0x48c3, [0xaf, 0xad, 0x25, 0xb9, 0x4f, 0x84, 0xa9, 0xb6]),
+        ID3D11UnorderedAccessView(ID3D11UnorderedAccessViewVtbl): ID3D11View = (0x28acf509, 0x7f5c, 0x48f6, [0x86, 0x11, 0xf3, 0x16, 0x01, 0x0a, 0x63, 0x80]),
+        ID3D11VertexShader(ID3D11VertexShaderVtbl): ID3D11DeviceChild = (0x3b301d64, 0xd678, 0x4289, [0x88, 0x97, 0x22, 0xf8, 0x92, 0x8b, 0x72, 0xf3]),
+        ID3D11HullShader(ID3D11HullShaderVtbl): ID3D11DeviceChild = (0x8e5c6061, 0x628a, 0x4c8e, [0x82, 0x64, 0xbb, 0xe4, 0x5c, 0xb3, 0xd5, 0xdd]),
+        ID3D11DomainShader(ID3D11DomainShaderVtbl): ID3D11DeviceChild = (0xf582c508, 0x0f36, 0x490c, [0x99, 0x77, 0x31, 0xee, 0xce, 0x26, 0x8c, 0xfa]),
+        ID3D11GeometryShader(ID3D11GeometryShaderVtbl): ID3D11DeviceChild = (0x38325b96, 0xeffb, 0x4022, [0xba, 0x02, 0x2e, 0x79, 0x5b, 0x70, 0x27, 0x5c]),
+        ID3D11PixelShader(ID3D11PixelShaderVtbl): ID3D11DeviceChild = (0xea82e40d, 0x51dc, 0x4f33, [0x93, 0xd4, 0xdb, 0x7c, 0x91, 0x25, 0xae, 0x8c]),
+        ID3D11ComputeShader(ID3D11ComputeShaderVtbl): ID3D11DeviceChild = (0x4f5b196e, 0xc2bd, 0x495e, [0xbd, 0x01, 0x1f, 0xde, 0xd3, 0x8e, 0x49, 0x69]),
+        ID3D11InputLayout(ID3D11InputLayoutVtbl): ID3D11DeviceChild = (0xe4819ddc, 0x4cf0, 0x4025, [0xbd, 0x26, 0x5d, 0xe8, 0x2a, 0x3e, 0x07, 0xb7]),
+        ID3D11SamplerState(ID3D11SamplerStateVtbl): ID3D11DeviceChild = (0xda6fea51, 0x564c, 0x4487, [0x98, 0x10, 0xf0, 0xd0, 0xf9, 0xb4, 0xe3, 0xa5]),
+        ID3D11Asynchronous(ID3D11AsynchronousVtbl): ID3D11DeviceChild = (0x4b35d0cd, 0x1e15, 0x4258, [0x9c, 0x98, 0x1b, 0x13, 0x33, 0xf6, 0xdd, 0x3b]),
+        ID3D11Query(ID3D11QueryVtbl): ID3D11Asynchronous = (0xd6c00747, 0x87b7, 0x425e, [0xb8, 0x4d, 0x44, 0xd1, 0x08, 0x56, 0x0a, 0xfd]),
+        ID3D11Predicate(ID3D11PredicateVtbl): ID3D11Query = (0x9eb576dd, 0x9f77, 0x4d86, [0x81, 0xaa, 0x8b, 0xab, 0x5f, 0xe4, 0x90, 0xe2]),
+        ID3D11Counter(ID3D11CounterVtbl): ID3D11Asynchronous = (0x6e8c49fb, 0xa371, 0x4770, [0xb4, 0x40, 0x29, 0x08, 0x60, 0x22, 0xb7, 0x41]),
+        ID3D11ClassInstance(ID3D11ClassInstanceVtbl): ID3D11DeviceChild = (0xa6cd7faa, 0xb0b7, 0x4a2f, [0x94, 0x36, 0x86, 0x62, 0xa6, 0x57, 0x97, 0xcb]),
+        ID3D11ClassLinkage(ID3D11ClassLinkageVtbl): ID3D11DeviceChild = (0xddf57cba, 0x9543, 0x46e4, [0xa1, 0x2b, 0xf2, 0x07, 0xa0, 0xfe, 0x7f, 0xed]),
+        ID3D11CommandList(ID3D11CommandListVtbl): ID3D11DeviceChild = (0xa24bc4d1, 0x769e, 0x43f7, [0x80, 0x13, 0x98, 0xff, 0x56, 0x6c, 0x18, 0xe2]),
+        ID3D11DeviceContext(ID3D11DeviceContextVtbl): ID3D11DeviceChild = (0xc0bfa96c, 0xe089, 0x44fb, [0x8e, 0xaf, 0x26, 0xf8, 0x79, 0x61, 0x90, 0xda]),
+        ID3D11VideoDecoder(ID3D11VideoDecoderVtbl): ID3D11DeviceChild = (0x3c9c5b51, 0x995d, 0x48d1, [0x9b, 0x8d, 0xfa, 0x5c, 0xae, 0xde, 0xd6, 0x5c]),
+        ID3D11VideoProcessorEnumerator(ID3D11VideoProcessorEnumeratorVtbl): ID3D11DeviceChild = (0x31627037, 0x53ab, 0x4200, [0x90, 0x61, 0x05, 0xfa, 0xa9, 0xab, 0x45, 0xf9]),
+        ID3D11VideoProcessor(ID3D11VideoProcessorVtbl): ID3D11DeviceChild = (0x1d7b0652, 0x185f, 0x41c6, [0x85, 0xce, 0x0c, 0x5b, 0xe3, 0xd4, 0xae, 0x6c]),
+        ID3D11AuthenticatedChannel(ID3D11AuthenticatedChannelVtbl): ID3D11DeviceChild = (0x3015a308, 0xdcbd, 0x47aa, [0xa7, 0x47, 0x19, 0x24, 0x86, 0xd1, 0x4d, 0x4a]),
+        ID3D11CryptoSession(ID3D11CryptoSessionVtbl): ID3D11DeviceChild = (0x9b32f9ad, 0xbdcc, 0x40a6, [0xa3, 0x9d, 0xd5, 0xc8, 0x65, 0x84, 0x57, 0x20]),
+        ID3D11VideoDecoderOutputView(ID3D11VideoDecoderOutputViewVtbl): ID3D11View = (0xc2931aea, 0x2a85, 0x4f20, [0x86, 0x0f, 0xfb, 0xa1, 0xfd, 0x25, 0x6e, 0x18]),
+        ID3D11VideoProcessorInputView(ID3D11VideoProcessorInputViewVtbl): ID3D11View = (0x11ec5a5f, 0x51dc, 0x4945, [0xab, 0x34, 0x6e, 0x8c, 0x21, 0x30, 0x0e, 0xa5]),
+        ID3D11VideoProcessorOutputView(ID3D11VideoProcessorOutputViewVtbl): ID3D11View = (0xa048285e, 0x25a9, 0x4527, [0xbd, 0x93, 0xd6, 0x8b, 0x68, 0xc4, 0x42, 0x54]),
+        ID3D11VideoContext(ID3D11VideoContextVtbl): ID3D11DeviceChild = (0x61f21c45, 0x3c0e, 0x4a74, [0x9c, 0xea, 0x67, 0x10, 0x0d, 0x9a, 0xd5, 0xe4]),
+        ID3D11VideoDevice(ID3D11VideoDeviceVtbl): IUnknown = (0x10ec4d5b, 0x975a, 0x4689, [0xb9, 0xe4, 0xd0, 0xaa, 0xc3, 0x0f, 0xe3, 0x33]),
+        ID3D11Device(ID3D11DeviceVtbl): IUnknown = (0xdb6f6ddb, 0xac77, 0x4e88, [0x82, 0x53, 0x81, 0x9d, 0xf9, 0xbb, 0xf1, 0x40]),
+    }
+}
+
+#[cfg(feature = "d3d11")]
+mod d3d11_1 {
+    use winapi::um::d3d11::{ID3D11BlendState, ID3D11Device, ID3D11DeviceChild, ID3D11DeviceContext, ID3D11RasterizerState, ID3D11VideoContext, ID3D11VideoDevice, ID3D11VideoProcessorEnumerator};
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d11_1::*;
+
+    winapi_interfaces! {
+        ID3D11BlendState1(ID3D11BlendState1Vtbl): ID3D11BlendState = (0xcc86fabe, 0xda55, 0x401d, [0x85, 0xe7, 0xe3, 0xc9, 0xde, 0x28, 0x77, 0xe9]),
+        ID3D11RasterizerState1(ID3D11RasterizerState1Vtbl): ID3D11RasterizerState = (0x1217d7a6, 0x5039, 0x418c, [0xb0, 0x42, 0x9c, 0xbe, 0x25, 0x6a, 0xfd, 0x6e]),
+        ID3DDeviceContextState(ID3DDeviceContextStateVtbl): ID3D11DeviceChild = (0x5c1e0d8a, 0x7c23, 0x48f9, [0x8c, 0x59, 0xa9, 0x29, 0x58, 0xce, 0xff, 0x11]),
+        ID3D11DeviceContext1(ID3D11DeviceContext1Vtbl): ID3D11DeviceContext = (0xbb2c6faa, 0xb5fb, 0x4082, [0x8e, 0x6b, 0x38, 0x8b, 0x8c, 0xfa, 0x90, 0xe1]),
+        ID3D11VideoContext1(ID3D11VideoContext1Vtbl): ID3D11VideoContext = (0xa7f026da, 0xa5f8, 0x4487, [0xa5, 0x64, 0x15, 0xe3, 0x43, 0x57, 0x65, 0x1e]),
+        ID3D11VideoDevice1(ID3D11VideoDevice1Vtbl): ID3D11VideoDevice = (0x29da1d51, 0x1321, 0x4454, [0x80, 0x4b, 0xf5, 0xfc, 0x9f, 0x86, 0x1f, 0x0f]),
+        ID3D11VideoProcessorEnumerator1(ID3D11VideoProcessorEnumerator1Vtbl): ID3D11VideoProcessorEnumerator = (0x465217f2, 0x5568, 0x43cf, [0xb5, 0xb9, 0xf6, 0x1d, 0x54, 0x53, 0x1c, 0xa1]),
+        ID3D11Device1(ID3D11Device1Vtbl): ID3D11Device = (0xa04bfb29, 0x08ef, 0x43d6, [0xa4, 0x9c, 0xa9, 0xbd, 0xbd, 0xcb, 0xe6, 0x86]),
+        ID3DUserDefinedAnnotation(ID3DUserDefinedAnnotationVtbl): IUnknown = (0xb2daad8b, 0x03d4, 0x4dbf, [0x95, 0xeb, 0x32, 0xab, 0x4b, 0x63, 0xd0, 0xab]),
+    }
+}
+
+#[cfg(feature = "d3d11")]
+mod d3d11_2 {
+    use winapi::um::d3d11_1::{ID3D11Device1, ID3D11DeviceContext1};
+    use winapi::um::d3d11_2::*;
+
+    winapi_interfaces! {
+        ID3D11DeviceContext2(ID3D11DeviceContext2Vtbl): ID3D11DeviceContext1 = (0x420d5b32, 0xb90c, 0x4da4, [0xbe, 0xf0, 0x35, 0x9f, 0x6a, 0x24, 0xa8, 0x3a]),
+        ID3D11Device2(ID3D11Device2Vtbl): ID3D11Device1 = (0x9d06dffa, 0xd1e5, 0x4d07, [0x83, 0xa8, 0x1b, 0xb1, 0x23, 0xf2, 0xf8, 0x41]),
+    }
+}
+
+#[cfg(feature = "d3d11")]
+mod d3d11sdklayers {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d11sdklayers::*;
+
+    winapi_interfaces! {
+        ID3D11Debug(ID3D11DebugVtbl): IUnknown = (0x79cf2233, 0x7536, 0x4948, [0x9d, 0x36, 0x1e, 0x46, 0x92, 0xdc, 0x57, 0x60]),
+        ID3D11SwitchToRef(ID3D11SwitchToRefVtbl): IUnknown = (0x1ef337e3, 0x58e7, 0x4f83, [0xa6, 0x92, 0xdb, 0x22, 0x1f, 0x5e, 0xd4, 0x7e]),
+        ID3D11TracingDevice(ID3D11TracingDeviceVtbl): IUnknown = (0x1911c771, 0x1587, 0x413e, [0xa7, 0xe0, 0xfb, 0x26, 0xc3, 0xde, 0x02, 0x68]),
+        ID3D11RefTrackingOptions(ID3D11RefTrackingOptionsVtbl): IUnknown = (0x193dacdf, 0x0db2, 0x4c05, [0xa5, 0x5c, 0xef, 0x06, 0xca, 0xc5, 0x6f, 0xd9]),
+        ID3D11RefDefaultTrackingOptions(ID3D11RefDefaultTrackingOptionsVtbl): IUnknown = (0x03916615, 0xc644, 0x418c, [0x9b, 0xf4, 0x75, 0xdb, 0x5b, 0xe6, 0x3c, 0xa0]),
+        ID3D11InfoQueue(ID3D11InfoQueueVtbl): IUnknown = (0x6543dbb6, 0x1b48, 0x42f5, [0xab, 0x82, 0xe9, 0x7e, 0xc7, 0x43, 0x26, 0xf6]),
+    }
+}
+
+#[cfg(feature = "d3d11")]
+mod d3d11shader {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d11shader::*;
+
+    winapi_interfaces! {
+        ID3D11ShaderReflection(ID3D11ShaderReflectionVtbl): IUnknown = (0x8d536ca1, 0x0cca, 0x4956, [0xa8, 0x37, 0x78, 0x69, 0x63, 0x75, 0x55, 0x84]),
+        ID3D11LibraryReflection(ID3D11LibraryReflectionVtbl): IUnknown = (0x54384f1b, 0x5b3e, 0x4bb7, [0xae, 0x01, 0x60, 0xba, 0x30, 0x97, 0xcb, 0xb6]),
+        ID3D11Module(ID3D11ModuleVtbl): IUnknown = (0xcac701ee, 0x80fc, 0x4122, [0x82, 0x42, 0x10, 0xb3, 0x9c, 0x8c, 0xec, 0x34]),
+        ID3D11ModuleInstance(ID3D11ModuleInstanceVtbl): IUnknown = (0x469e07f7, 0x045a, 0x48d5, [0xaa, 0x12, 0x68, 0xa4, 0x78, 0xcd, 0xf7, 0x5d]),
+        ID3D11Linker(ID3D11LinkerVtbl): IUnknown = (0x59a6cd0e, 0xe10d, 0x4c1f, [0x88, 0xc0, 0x63, 0xab, 0xa1, 0xda, 0xf3, 0x0e]),
+        ID3D11LinkingNode(ID3D11LinkingNodeVtbl): IUnknown = (0xd80dd70c, 0x8d2f, 0x4751, [0x94, 0xa1, 0x03, 0xc7, 0x9b, 0x35, 0x56, 0xdb]),
+        ID3D11FunctionLinkingGraph(ID3D11FunctionLinkingGraphVtbl): IUnknown = (0x54133220, 0x1ce8, 0x43d3, [0x82, 0x36, 0x98, 0x55, 0xc5, 0xce, 0xec, 0xff]),
+    }
+}
+
+#[cfg(feature = "d3d12")]
+mod d3d12 {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d12::*;
+
+    winapi_interfaces! {
+        ID3D12RootSignature(ID3D12RootSignatureVtbl): ID3D12DeviceChild = (0xc54a6b66, 0x72df, 0x4ee8, [0x8b, 0xe5, 0xa9, 0x46, 0xa1, 0x42, 0x92, 0x14]),
+        ID3D12RootSignatureDeserializer(ID3D12RootSignatureDeserializerVtbl): IUnknown = (0x34ab647b, 0x3cc8, 0x46ac, [0x84, 0x1b, 0xc0, 0x96, 0x56, 0x45, 0xc0, 0x46]),
+        ID3D12VersionedRootSignatureDeserializer(ID3D12VersionedRootSignatureDeserializerVtbl): IUnknown = (0x7f91ce67, 0x090c, 0x4bb7, [0xb7, 0x8e, 0xed, 0x8f, 0xf2, 0xe3, 0x1d, 0xa0]),
+        ID3D12Object(ID3D12ObjectVtbl): IUnknown = (0xc4fec28f, 0x7966, 0x4e95, [0x9f, 0x94, 0xf4, 0x31, 0xcb, 0x56, 0xc3, 0xb8]),
+        ID3D12DeviceChild(ID3D12DeviceChildVtbl): ID3D12Object = (0x905db94b, 0xa00c, 0x4140, [0x9d, 0xf5, 0x2b, 0x64, 0xca, 0x9e, 0xa3, 0x57]),
+        ID3D12Pageable(ID3D12PageableVtbl): ID3D12DeviceChild = (0x63ee58fb, 0x1268, 0x4835, [0x86, 0xda, 0xf0, 0x08, 0xce, 0x62, 0xf0, 0xd6]),
+        ID3D12Heap(ID3D12HeapVtbl): ID3D12Pageable = (0x6b3b2502, 0x6e51, 0x45b3, [0x90, 0xee, 0x98, 0x84, 0x26, 0x5e, 0x8d, 0xf3]),
+        ID3D12Resource(ID3D12ResourceVtbl): ID3D12Pageable = (0x696442be, 0xa72e, 0x4059, [0xbc, 0x79, 0x5b, 0x5c, 0x98, 0x04, 0x0f, 0xad]),
+        ID3D12CommandAllocator(ID3D12CommandAllocatorVtbl): ID3D12Pageable = (0x6102dee4, 0xaf59, 0x4b09, [0xb9, 0x99, 0xb4, 0x4d, 0x73, 0xf0, 0x9b, 0x24]),
+        ID3D12Fence(ID3D12FenceVtbl): ID3D12Pageable = (0x0a753dcf, 0xc4d8, 0x4b91, [0xad, 0xf6, 0xbe, 0x5a, 0x60, 0xd9, 0x5a, 0x76]),
+        ID3D12PipelineState(ID3D12PipelineStateVtbl): ID3D12Pageable = (0x765a30f3, 0xf624, 0x4c6f, [0xa8, 0x28, 0xac, 0xe9, 0x48, 0x62, 0x24, 0x45]),
+        ID3D12DescriptorHeap(ID3D12DescriptorHeapVtbl): ID3D12Pageable = (0x8efb471d, 0x616c, 0x4f49, [0x90, 0xf7, 0x12, 0x7b, 0xb7, 0x63, 0xfa, 0x51]),
+        ID3D12QueryHeap(ID3D12QueryHeapVtbl): ID3D12Pageable = (0x0d9658ae, 0xed45, 0x469e, [0xa6, 0x1d, 0x97, 0x0e, 0xc5, 0x83, 0xca, 0xb4]),
+        ID3D12CommandSignature(ID3D12CommandSignatureVtbl): ID3D12Pageable = (0xc36a797c, 0xec80, 0x4f0a, [0x89, 0x85, 0xa7, 0xb2, 0x47, 0x50, 0x82, 0xd1]),
+        ID3D12CommandList(ID3D12CommandListVtbl): ID3D12DeviceChild = (0x7116d91c, 0xe7e4, 0x47ce, [0xb8, 0xc6, 0xec, 0x81, 0x68, 0xf4, 0x37, 0xe5]),
+        ID3D12GraphicsCommandList(ID3D12GraphicsCommandListVtbl): ID3D12CommandList = (0x5b160d0f, 0xac1b, 0x4185, [0x8b, 0xa8, 0xb3, 0xae, 0x42, 0xa5, 0xa4, 0x55]),
+        ID3D12GraphicsCommandList1(ID3D12GraphicsCommandList1Vtbl): ID3D12GraphicsCommandList = (0x553103fb, 0x1fe7, 0x4557, [0xbb, 0x38, 0x94, 0x6d, 0x7d, 0x0e, 0x7c, 0xa7]),
+        ID3D12CommandQueue(ID3D12CommandQueueVtbl): ID3D12Pageable = (0x0ec870a6, 0x5d7e, 0x4c22, [0x8c, 0xfc, 0x5b, 0xaa, 0xe0, 0x76, 0x16, 0xed]),
+        ID3D12Device(ID3D12DeviceVtbl): ID3D12Object = (0x189819f1, 0x1db6, 0x4b57, [0xbe, 0x54, 0x18, 0x21, 0x33, 0x9b, 0x85, 0xf7]),
+        ID3D12PipelineLibrary(ID3D12PipelineLibraryVtbl): ID3D12DeviceChild = (0xc64226a8, 0x9201, 0x46af, [0xb4, 0xcc, 0x53, 0xfb, 0x9f, 0xf7, 0x41, 0x4f]),
+        ID3D12PipelineLibrary1(ID3D12PipelineLibrary1Vtbl): ID3D12PipelineLibrary = (0x80eabf42, 0x2568, 0x4e5e, [0xbd, 0x82, 0xc3, 0x7f, 0x86, 0x96, 0x1d, 0xc3]),
+        ID3D12Device1(ID3D12Device1Vtbl): ID3D12Device = (0x77acce80, 0x638e, 0x4e65, [0x88, 0x95, 0xc1, 0xf2, 0x33, 0x86, 0x86, 0x3e]),
+        ID3D12Device2(ID3D12Device2Vtbl): ID3D12Device1 = (0x30baa41e, 0xb15b, 0x475c, [0xa0, 0xbb, 0x1a, 0xf5, 0xc5, 0xb6, 0x43, 0x28]),
+        ID3D12Tools(ID3D12ToolsVtbl): IUnknown = (0x7071e1f0, 0xe84b, 0x4b33, [0x97, 0x4f, 0x12, 0xfa, 0x49, 0xde, 0x65, 0xc5]),
+    }
+}
+
+#[cfg(feature = "d3d12")]
+mod d3d12sdklayers {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d12sdklayers::*;
+
+    winapi_interfaces! {
+        ID3D12Debug(ID3D12DebugVtbl): IUnknown = (0x344488b7, 0x6846, 0x474b, [0xb9, 0x89, 0xf0, 0x27, 0x44, 0x82, 0x45, 0xe0]),
+        ID3D12Debug1(ID3D12Debug1Vtbl): IUnknown = (0xaffaa4ca, 0x63fe, 0x4d8e, [0xb8, 0xad, 0x15, 0x90, 0x00, 0xaf, 0x43, 0x04]),
+        ID3D12Debug2(ID3D12Debug2Vtbl): IUnknown = (0x93a665c4, 0xa3b2, 0x4e5d, [0xb6, 0x92, 0xa2, 0x6a, 0xe1, 0x4e, 0x33, 0x74]),
+        ID3D12DebugDevice1(ID3D12DebugDevice1Vtbl): IUnknown = (0x3febd6dd, 0x4973, 0x4787, [0x81, 0x94, 0xe4, 0x5f, 0x9e, 0x28, 0x92, 0x3e]),
+        ID3D12DebugDevice(ID3D12DebugDeviceVtbl): IUnknown = (0x3febd6dd, 0x4973, 0x4787, [0x81, 0x94, 0xe4, 0x5f, 0x9e, 0x28, 0x92, 0x3e]),
+        ID3D12DebugCommandQueue(ID3D12DebugCommandQueueVtbl): IUnknown = (0x09e0bf36, 0x54ac, 0x484f, [0x88, 0x47, 0x4b, 0xae, 0xea, 0xb6, 0x05, 0x3a]),
+        ID3D12DebugCommandList1(ID3D12DebugCommandList1Vtbl): IUnknown = (0x102ca951, 0x311b, 0x4b01, [0xb1, 0x1f, 0xec, 0xb8, 0x3e, 0x06, 0x1b, 0x37]),
+        ID3D12DebugCommandList(ID3D12DebugCommandListVtbl): IUnknown = (0x09e0bf36, 0x54ac, 0x484f, [0x88, 0x47, 0x4b, 0xae, 0xea, 0xb6, 0x05, 0x3f]),
+        ID3D12InfoQueue(ID3D12InfoQueueVtbl): IUnknown = (0x0742a90b, 0xc387, 0x483f, [0xb9, 0x46, 0x30, 0xa7, 0xe4, 0xe6, 0x14, 0x58]),
+    }
+}
+
+#[cfg(feature = "d3d12")]
+mod d3d12shader {
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::d3d12shader::*;
+
+    winapi_interfaces! {
+        ID3D12LibraryReflection(ID3D12LibraryReflectionVtbl): IUnknown = (0x8e349d19, 0x54db, 0x4a56, [0x9d, 0xc9, 0x11, 0x9d, 0x87, 0xbd, 0xb8, 0x4]),
+        ID3D12ShaderReflection(ID3D12ShaderReflectionVtbl): IUnknown = (0x5a58797d, 0xa72c, 0x478d, [0x8b, 0xa2, 0xef, 0xc6, 0xb0, 0xef, 0xe8, 0x8e]),
+    }
+}
+
+#[cfg(feature = "wincodec")]
+mod wincodec {
+    use winapi::um::objidlbase::IStream;
+    use winapi::um::unknwnbase::IUnknown;
+    use winapi::um::wincodec::*;
+
+    winapi_interfaces! {
+        IWICPalette(IWICPaletteVtbl): IUnknown = (0x00000040, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICBitmapSource(IWICBitmapSourceVtbl): IUnknown = (0x00000120, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICFormatConverter(IWICFormatConverterVtbl): IWICBitmapSource = (0x00000301, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICPlanarFormatConverter(IWICPlanarFormatConverterVtbl): IWICBitmapSource = (0xbebee9cb, 0x83b0, 0x4dcc, [0x81, 0x32, 0xb0, 0xaa, 0xa5, 0x5e, 0xac, 0x96]),
+        IWICBitmapScaler(IWICBitmapScalerVtbl): IWICBitmapSource = (0x00000302, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICBitmapClipper(IWICBitmapClipperVtbl): IWICBitmapSource = (0xe4fbcf03, 0x223d, 0x4e81, [0x93, 0x33, 0xd6, 0x35, 0x55, 0x6d, 0xd1, 0xb5]),
+        IWICBitmapFlipRotator(IWICBitmapFlipRotatorVtbl): IWICBitmapSource = (0x5009834f, 0x2d6a, 0x41ce, [0x9e, 0x1b, 0x17, 0xc5, 0xaf, 0xf7, 0xa7, 0x82]),
+        IWICBitmapLock(IWICBitmapLockVtbl): IUnknown = (0x00000123, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICBitmap(IWICBitmapVtbl): IWICBitmapSource = (0x00000121, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICColorContext(IWICColorContextVtbl): IUnknown = (0x3c613a02, 0x34b2, 0x44ea, [0x9a, 0x7c, 0x45, 0xae, 0xa9, 0xc6, 0xfd, 0x6d]),
+        IWICColorTransform(IWICColorTransformVtbl): IWICBitmapSource = (0xb66f034f, 0xd0e2, 0x40ab, [0xb4, 0x36, 0x6d, 0xe3, 0x9e, 0x32, 0x1a, 0x94]),
+        IWICFastMetadataEncoder(IWICFastMetadataEncoderVtbl): IUnknown = (0xb84e2c09, 0x78c9, 0x4ac4, [0x8b, 0xd3, 0x52, 0x4a, 0xe1, 0x66, 0x3a, 0x2f]),
+        IWICStream(IWICStreamVtbl): IStream = (0x135ff860, 0x22b7, 0x4ddf, [0xb0, 0xf6, 0x21, 0x8f, 0x4f, 0x29, 0x9a, 0x43]),
+        IWICEnumMetadataItem(IWICEnumMetadataItemVtbl): IUnknown = (0xdc2bb46d, 0x3f07, 0x481e, [0x86, 0x25, 0x22, 0x0c, 0x4a, 0xed, 0xbb, 0x33]),
+        IWICMetadataQueryReader(IWICMetadataQueryReaderVtbl): IUnknown = (0x30989668, 0xe1c9, 0x4597, [0xb3, 0x95, 0x45, 0x8e, 0xed, 0xb8, 0x08, 0xdf]),
+        IWICMetadataQueryWriter(IWICMetadataQueryWriterVtbl): IWICMetadataQueryReader = (0xa721791a, 0x0def, 0x4d06, [0xbd, 0x91, 0x21, 0x18, 0xbf, 0x1d, 0xb1, 0x0b]),
+        IWICBitmapEncoder(IWICBitmapEncoderVtbl): IUnknown = (0x00000103, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICBitmapFrameEncode(IWICBitmapFrameEncodeVtbl): IUnknown = (0x00000105, 0xa8f2, 0x4877, [0xba, 0x0a, 0xfd, 0x2b, 0x66, 0x45, 0xfb, 0x94]),
+        IWICPlanarBitmapFrameEncode(IWICPlanarBitmapFrameEncodeVtbl): IUnknown = (0xf928b7b8, 0x2221, 0x40c1, [0xb7, 0x2e, 0x7e, 0x82, 0xf1, 0x97, 0x4d, 0x1a]),
+        IWICImageEncoder(IWICImageEncoderVtbl): IUnknown = (0x04c75bf8, 0x3ce1, 0x473b, [0xac, 0xc5, 0x3c, 0xc4, 0xf5, 0xe9, 0x49, 0x99]),
+        IWICBitmapDecoder(IWICBitmapDecoderVtbl): IUnknown = (0x9edde9e7, 0x8dee, 0x47ea, [0x99, 0xdf, 0xe6, 0xfa, 0xf2, 0xed, 0x44, 0xbf]),
+        IWICBitmapSourceTransform(IWICBitmapSourceTransformVtbl): IUnknown = (0x3b16811b, 0x6a43, 0x4ec9, [0xb7, 0x13, 0x3d, 0x5a, 0x0c, 0x13, 0xb9, 0x40]),
+        IWICPlanarBitmapSourceTransform(IWICPlanarBitmapSourceTransformVtbl): IUnknown = (0x3aff9cce, 0xbe95, 0x4303, [0xb9, 0x27, 0xe7, 0xd1, 0x6f, 0xf4, 0xa6, 0x13]),
+        IWICBitmapFrameDecode(IWICBitmapFrameDecodeVtbl): IWICBitmapSource = (0x3b16811b, 0x6a43, 0x4ec9, [0xa8, 0x13, 0x3d, 0x93, 0x0c, 0x13, 0xb9, 0x40]),
+        IWICProgressiveLevelControl(IWICProgressiveLevelControlVtbl): IUnknown = (0xdaac296f, 0x7aa5, 0x4dbf, [0x8d, 0x15, 0x22, 0x5c, 0x59, 0x76, 0xf8, 0x91]),
+        IWICProgressCallback(IWICProgressCallbackVtbl): IUnknown = (0x4776f9cd, 0x9517, 0x45fa, [0xbf, 0x24, 0xe8, 0x9c, 0x5e, 0xc5, 0xc6, 0x0c]),
+        IWICBitmapCodecProgressNotification(IWICBitmapCodecProgressNotificationVtbl): IUnknown = (0x64c1024e, 0xc3cf, 0x4462, [0x80, 0x78, 0x88, 0xc2, 0xb1, 0x1c, 0x46, 0xd9]),
+        IWICComponentInfo(IWICComponentInfoVtbl): IUnknown = (0x23bc3f0a, 0x698b, 0x4357, [0x88, 0x6b, 0xf2, 0x4d, 0x50, 0x67, 0x13, 0x34]),
+        IWICFormatConverterInfo(IWICFormatConverterInfoVtbl): IWICComponentInfo = (0x9f34fb65, 0x13f4, 0x4f15, [0xbc, 0x57, 0x37, 0x26, 0xb5, 0xe5, 0x3d, 0x9f]),
+        IWICBitmapCodecInfo(IWICBitmapCodecInfoVtbl): IWICComponentInfo = (0xe87a44c4, 0xb76e, 0x4c47, [0x8b, 0x09, 0x29, 0x8e, 0xb1, 0x2a, 0x27, 0x14]),
+        IWICBitmapEncoderInfo(IWICBitmapEncoderInfoVtbl): IWICBitmapCodecInfo = (0x94c9b4ee, 0xa09f, 0x4f92, [0x8a, 0x1e, 0x4a, 0x9b, 0xce, 0x7e, 0x76, 0xfb]),
+        IWICBitmapDecoderInfo(IWICBitmapDecoderInfoVtbl): IWICBitmapCodecInfo = (0xd8cd007f, 0xd08f, 0x4191, [0x9b, 0xfc, 0x23, 0x6e, 0xa7, 0xf0, 0xe4, 0xb5]),
+        IWICPixelFormatInfo(IWICPixelFormatInfoVtbl): IWICComponentInfo = (0xe8eda601, 0x3d48, 0x431a, [0xab, 0x44, 0x69, 0x05, 0x9b, 0xe8, 0x8b, 0xbe]),
+        IWICPixelFormatInfo2(IWICPixelFormatInfo2Vtbl): IWICPixelFormatInfo = (0xa9db33a2, 0xaf5f, 0x43c7, [0xb6, 0x79, 0x74, 0xf5, 0x98, 0x4b, 0x5a, 0xa4]),
+        IWICImagingFactory(IWICImagingFactoryVtbl): IUnknown = (0xec5ec8a9, 0xc395, 0x4314, [0x9c, 0x77, 0x54, 0xd7, 0xa9, 0x35, 0xff, 0x70]),
+        IWICImagingFactory2(IWICImagingFactory2Vtbl): IWICImagingFactory = (0x7b816b45, 0x1996, 0x4476, [0xb1, 0x32, 0xde, 0x9e, 0x24, 0x7c, 0x8a, 0xf0]),
+        IWICDevelopRawNotificationCallback(IWICDevelopRawNotificationCallbackVtbl): IUnknown = (0x95c75a6e, 0x3e8c, 0x4ec2, [0x85, 0xa8, 0xae, 0xbc, 0xc5, 0x51, 0xe5, 0x9b]),
+        IWICDevelopRaw(IWICDevelopRawVtbl): IWICBitmapFrameDecode = (0xfbec5e44, 0xf7be, 0x4b65, [0xb7, 0xf8, 0xc0, 0xc8, 0x1f, 0xef, 0x02, 0x6d]),
+        IWICDdsDecoder(IWICDdsDecoderVtbl): IUnknown = (0x409cd537, 0x8532, 0x40cb, [0x97, 0x74, 0xe2, 0xfe, 0xb2, 0xdf, 0x4e, 0x9c]),
+        IWICDdsEncoder(IWICDdsEncoderVtbl): IUnknown = (0x5cacdb4c, 0x407e, 0x41b3, [0xb9, 0x36, 0xd0, 0xf0, 0x10, 0xcd, 0x67, 0x32]),
+        IWICDdsFrameDecode(IWICDdsFrameDecodeVtbl): IUnknown = (0x3d4c0c61, 0x18a4, 0x41e4, [0xbd, 0x80, 0x48, 0x1a, 0x4f, 0xc9, 0xf4, 0x64]),
+        IWICJpegFrameDecode(IWICJpegFrameDecodeVtbl): IUnknown = (0x8939f66e, 0xc46a, 0x4c21, [0xa9, 0xd1, 0x98, 0xb3, 0x27, 0xce, 0x16, 0x79]),
+        IWICJpegFrameEncode(IWICJpegFrameEncodeVtbl): IUnknown = (0x2f0c601f, 0xd2c6, 0x468c, [0xab, 0xfa, 0x49, 0x49, 0x5d, 0x98, 0x3e, 0xd1]),
+    }
+}
@@ -0,0 +1,145 @@
+//! The symbols and messages of well-known HRESULTs.
+//!
+//! This table is a subset of winerror.h of the Windows SDK, sorted by the values, and the messages
+//! are copied from winerror.h. The HRESULTs without a message, such as `S_FALSE`, and the messages
+//! with inserts such as `%1` have an empty message. `scripts/hresult_table.py` writes the full
+//! table from winerror.h by the same rules.
+
+pub(crate) static TABLE: &[(u32, &str, &str)] = &[
+    (0x00000000, "S_OK", "The operation completed successfully."),
+    (0x00000001, "S_FALSE", ""),
+    (0x8000000A, "E_PENDING", "The data necessary to complete this operation is not yet available."),
+    (0x8000000B, "E_BOUNDS", "The operation attempted to access data outside the valid range"),
+    (0x8000000C, "E_CHANGED_STATE", "A concurrent or interleaved operation changed the state of the object, invalidating this operation."),
+    (0x8000000D, "E_ILLEGAL_STATE_CHANGE", "An illegal state change was requested."),
+    (0x8000000E, "E_ILLEGAL_METHOD_CALL", "A method was called at an unexpected time."),
+    (0x80004001, "E_NOTIMPL", "Not implemented"),
+    (0x80004002, "E_NOINTERFACE", "No such interface supported"),
+    (0x80004003, "E_POINTER", "Invalid pointer"),
+    (0x80004004, "E_ABORT", "Operation aborted"),
+    (0x80004005, "E_FAIL", "Unspecified error"),
+    (0x8000FFFF, "E_UNEXPECTED", "Catastrophic failure"),
+    (0x80010105, "RPC_E_SERVERFAULT", "The server threw an exception."),
+    (0x80010106, "RPC_E_CHANGED_MODE", "Cannot change thread mode after it is set."),
+    (0x80010108, "RPC_E_DISCONNECTED", "The object invoked has disconnected from its clients."),
+    (0x8001010D, "RPC_E_CANTCALLOUT_ININPUTSYNCCALL", "An outgoing call cannot be made since the application is dispatching an input-synchronous call."),
+    (0x8001010E, "RPC_E_WRONG_THREAD", "The application called an interface that was marshalled for a different thread."),
+    (0x80020001, "DISP_E_UNKNOWNINTERFACE", "Unknown interface."),
+    (0x80020003, "DISP_E_MEMBERNOTFOUND", "Member not found."),
+    (0x80020004, "DISP_E_PARAMNOTFOUND", "Parameter not found."),
+    (0x80020005, "DISP_E_TYPEMISMATCH", "Type mismatch."),
+    (0x80020006, "DISP_E_UNKNOWNNAME", "Unknown name."),
+    (0x80020007, "DISP_E_NONAMEDARGS", "No named arguments."),
+    (0x80020008, "DISP_E_BADVARTYPE", "Bad variable type."),
+    (0x80020009, "DISP_E_EXCEPTION", "Exception occurred."),
+    (0x8002000A, "DISP_E_OVERFLOW", "Out of present range."),
+    (0x8002000B, "DISP_E_BADINDEX", "Invalid index."),
+    (0x8002000E, "DISP_E_BADPARAMCOUNT", "Invalid number of parameters."),
+    (0x8002000F, "DISP_E_PARAMNOTOPTIONAL", "Parameter not optional."),
+    (0x8002802B, "TYPE_E_ELEMENTNOTFOUND", "Element not found."),
+    (0x80030001, "STG_E_INVALIDFUNCTION", "Unable to perform requested operation."),
+    (0x80030002, "STG_E_FILENOTFOUND", ""),
+    (0x80030003, "STG_E_PATHNOTFOUND", ""),
+    (0x80030004, "STG_E_TOOMANYOPENFILES", "There are insufficient resources to open another file."),
+    (0x80030005, "STG_E_ACCESSDENIED", "Access Denied."),
+    (0x80030006, "STG_E_INVALIDHANDLE", "Attempted an operation on an invalid object."),
+    (0x80030008, "STG_E_INSUFFICIENTMEMORY", "There is insufficient memory available to complete operation."),
+    (0x80030009, "STG_E_INVALIDPOINTER", "Invalid pointer error."),
+    (0x80030012, "STG_E_NOMOREFILES", "There are no more entries to return."),
+    (0x8003001D, "STG_E_WRITEFAULT", "A disk error occurred during a write operation."),
+    (0x8003001E, "STG_E_READFAULT", "A disk error occurred during a read operation."),
+    (0x80030020, "STG_E_SHAREVIOLATION", "A share violation has occurred."),
+    (0x80030021, "STG_E_LOCKVIOLATION", "A lock violation has occurred."),
+    (0x80030050, "STG_E_FILEALREADYEXISTS", ""),
+    (0x80030057, "STG_E_INVALIDPARAMETER", "Invalid parameter error."),
+    (0x80030070, "STG_E_MEDIUMFULL", "There is insufficient disk space to complete operation."),
+    (0x800300FC, "STG_E_INVALIDNAME", ""),
+    (0x80040110, "CLASS_E_NOAGGREGATION", "Class does not support aggregation (or class object is remote)"),
+    (0x80040111, "CLASS_E_CLASSNOTAVAILABLE", "ClassFactory cannot supply requested class"),
+    (0x80040154, "REGDB_E_CLASSNOTREG", "Class not registered"),
+    (0x800401F0, "CO_E_NOTINITIALIZED", "CoInitialize has not been called."),
+    (0x800401F1, "CO_E_ALREADYINITIALIZED", "CoInitialize has already been called."),
+    (0x80070001, "ERROR_INVALID_FUNCTION", "Incorrect function."),
+    (0x80070002, "ERROR_FILE_NOT_FOUND", "The system cannot find the file specified."),
+    (0x80070003, "ERROR_PATH_NOT_FOUND", "The system cannot find the path specified."),
+    (0x80070004, "ERROR_TOO_MANY_OPEN_FILES", "The system cannot open the file."),
+    (0x80070005, "E_ACCESSDENIED", "Access is denied."),
+    (0x80070006, "E_HANDLE", "The handle is invalid."),
+    (0x80070008, "ERROR_NOT_ENOUGH_MEMORY", "Not enough memory resources are available to process this command."),
+    (0x8007000D, "ERROR_INVALID_DATA", "The data is invalid."),
+    (0x8007000E, "E_OUTOFMEMORY", "Not enough memory resources are available to complete this operation."),
+    (0x80070015, "ERROR_NOT_READY", "The device is not ready."),
+    (0x80070020, "ERROR_SHARING_VIOLATION", "The process cannot access the file because it is being used by another process."),
+    (0x80070026, "ERROR_HANDLE_EOF", "Reached the end of the file."),
+    (0x80070032, "ERROR_NOT_SUPPORTED", "The request is not supported."),
+    (0x80070050, "ERROR_FILE_EXISTS", "The file exists."),
+    (0x80070057, "E_INVALIDARG", "The parameter is incorrect."),
+    (0x8007006D, "ERROR_BROKEN_PIPE", "The pipe has been ended."),
+    (0x80070070, "ERROR_DISK_FULL", "There is not enough space on the disk."),
+    (0x8007007A, "ERROR_INSUFFICIENT_BUFFER", "The data area passed to a system call is too small."),
+    (0x8007007B, "ERROR_INVALID_NAME", "The filename, directory name, or volume label syntax is incorrect."),
+    (0x800700AA, "ERROR_BUSY", "The requested resource is in use."),
+    (0x800700B7, "ERROR_ALREADY_EXISTS", "Cannot create a file when that file already exists."),
+    (0x800700EA, "ERROR_MORE_DATA", "More data is available."),
+    (0x80070103, "ERROR_NO_MORE_ITEMS", "No more data is available."),
+    (0x800703E3, "ERROR_OPERATION_ABORTED", "The I/O operation has been aborted because of either a thread exit or an application request."),
+    (0x800703E5, "ERROR_IO_PENDING", "Overlapped I/O operation is in progress."),
+    (0x80070490, "ERROR_NOT_FOUND", "Element not found."),
+    (0x800704C7, "ERROR_CANCELLED", "The operation was canceled by the user."),
+    (0x800705B4, "ERROR_TIMEOUT", "This operation returned because the timeout period expired."),
+    (0x8007139F, "ERROR_INVALID_STATE", "The group or resource is not in the correct state to perform the requested operation."),
+    (0x80080005, "CO_E_SERVER_EXEC_FAILURE", "Server execution failed"),
+    (0x887A0001, "DXGI_ERROR_INVALID_CALL", "The application made a call that is invalid. Either the parameters of the call or the state of some object was incorrect. Enable the D3D debug layer in order to see details via debug messages."),
+    (0x887A0002, "DXGI_ERROR_NOT_FOUND", "The object was not found. If calling IDXGIFactory::EnumAdaptes, there is no adapter with the specified ordinal."),
+    (0x887A0003, "DXGI_ERROR_MORE_DATA", "The caller did not supply a sufficiently large buffer."),
+    (0x887A0004, "DXGI_ERROR_UNSUPPORTED", "The specified device interface or feature level is not supported on this system."),
+    (0x887A0005, "DXGI_ERROR_DEVICE_REMOVED", "The GPU device instance has been suspended. Use GetDeviceRemovedReason to determine the appropriate action."),
+    (0x887A0006, "DXGI_ERROR_DEVICE_HUNG", "The GPU will not respond to more commands, most likely because of an invalid command passed by the calling application."),
+    (0x887A0007, "DXGI_ERROR_DEVICE_RESET", "The GPU will not respond to more commands, most likely because some other application submitted invalid commands. The calling application should re-create the device and continue."),
+    (0x887A000A, "DXGI_ERROR_WAS_STILL_DRAWING", "The GPU was busy at the moment when the call was made, and the call was neither executed nor scheduled."),
+    (0x887A0020, "DXGI_ERROR_DRIVER_INTERNAL_ERROR", "An internal issue prevented the driver from carrying out the specified operation. The driver's state is probably suspect, and the application should not continue."),
+    (0x887A0022, "DXGI_ERROR_NOT_CURRENTLY_AVAILABLE", "The requested functionality is not supported by the device or the driver."),
+    (0x887A0026, "DXGI_ERROR_ACCESS_LOST", "The desktop duplication interface is invalid. The desktop duplication interface typically becomes invalid when a different type of image is displayed on the desktop."),
+    (0x887A0027, "DXGI_ERROR_WAIT_TIMEOUT", "The time-out interval elapsed before the next desktop frame was available."),
+    (0x887A002D, "DXGI_ERROR_SDK_COMPONENT_MISSING", "The operation depends on an SDK component that is missing or mismatched."),
+    (0x88982F04, "WINCODEC_ERR_WRONGSTATE", "The codec is in the wrong state."),
+    (0x88982F05, "WINCODEC_ERR_VALUEOUTOFRANGE", "The value is out of range."),
+    (0x88982F07, "WINCODEC_ERR_UNKNOWNIMAGEFORMAT", "The image format is unknown."),
+    (0x88982F0B, "WINCODEC_ERR_UNSUPPORTEDVERSION", "The SDK version is unsupported."),
+    (0x88982F0C, "WINCODEC_ERR_NOTINITIALIZED", "The component is not initialized."),
+    (0x88982F50, "WINCODEC_ERR_COMPONENTNOTFOUND", "The component cannot be found."),
+    (0x88982F80, "WINCODEC_ERR_UNSUPPORTEDPIXELFORMAT", "The bitmap pixel format is unsupported."),
+    (0x88982F81, "WINCODEC_ERR_UNSUPPORTEDOPERATION", "The operation is unsupported."),
+    (0x8899000C, "D2DERR_RECREATE_TARGET", "There has been a presentation error that may be recoverable. The caller needs to recreate, rerender the entire frame, and reattempt present."),
+];
+
+/// Returns the symbol and the message of `code`.
+pub(crate) fn find(code: u32) -> Option<(&'static str, &'static str)> {
+    TABLE
+        .binary_search_by_key(&code, |&(value, _, _)| value)
+        .ok()
+        .map(|i| (TABLE[i].1, TABLE[i].2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_test() {
+        assert!(TABLE.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn find_test() {
+        assert_eq!(find(0x80070005), Some(("E_ACCESSDENIED", "Access is denied.")));
+        assert_eq!(find(0x80070000), None);
+        assert_eq!(find(0x00000001), Some(("S_FALSE", "")));
+        assert_eq!(find(0x80030002), Some(("STG_E_FILENOTFOUND", "")));
+    }
+
+    #[test]
+    fn inserts_test() {
+        assert!(TABLE.iter().all(|&(_, _, message)| !message.contains('%')));
+    }
+}
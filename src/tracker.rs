@@ -0,0 +1,394 @@
+//! Tracking of the references owned by `ComPtr`.
+//!
+//! This module is available with the `track-refs` feature.
+//! The references are recorded with the interface pointer, the IID and the backtrace
+//! when a `ComPtr` is created or cloned and when [`ComPtr::add_ref`] is called.
+//! When a `ComPtr` is dropped, the latest `New` or `Clone` record of the same pointer and IID is
+//! removed, and [`ComPtr::release`] removes the latest `AddRef` record. [`ComPtr::into_raw`] also
+//! removes the record because the reference is no longer owned by a `ComPtr`, and
+//! [`ComPtr::upcast`] changes the IID of the record.
+//!
+//! The references that were recorded on the thread and not released when a
+//! [`ComApartment`](crate::ComApartment) is uninitialized are printed to stderr by
+//! [`print_leaks`], or passed to the hook set by [`set_leak_hook`].
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "track-refs")]
+//! # {
+//! use com_ptr::tracker::Checkpoint;
+//! use com_ptr::{com_class, sys::IUnknown};
+//!
+//! #[com_class(IUnknown)]
+//! struct Object;
+//!
+//! let checkpoint = Checkpoint::new();
+//! let p = Object.into_com_ptr();
+//! p.add_ref();
+//! assert_eq!(checkpoint.leaks().len(), 2);
+//! unsafe { p.release() };
+//! drop(p);
+//! checkpoint.assert_no_leaks();
+//! # }
+//! ```
+
+use crate::sys::*;
+use crate::{ComPtr, Guid};
+use std::backtrace::Backtrace;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::ThreadId;
+
+/// The operations that acquire a reference.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RefKind {
+    /// A `ComPtr` was created with the reference.
+    New,
+    /// A `ComPtr` was cloned.
+    Clone,
+    /// [`ComPtr::add_ref`] was called.
+    AddRef,
+}
+
+/// A reference that is not released.
+#[derive(Clone, Debug)]
+pub struct TrackedRef {
+    id: u64,
+    object: usize,
+    iid: Guid,
+    kind: RefKind,
+    thread: ThreadId,
+    backtrace: Arc<Backtrace>,
+}
+
+impl TrackedRef {
+    /// Returns the pointer of the interface.
+    #[inline]
+    pub fn object(&self) -> *mut c_void {
+        self.object as *mut c_void
+    }
+
+    /// Returns the IID of the interface.
+    #[inline]
+    pub fn iid(&self) -> Guid {
+        self.iid
+    }
+
+    /// Returns the operation that acquired the reference.
+    #[inline]
+    pub fn kind(&self) -> RefKind {
+        self.kind
+    }
+
+    /// Returns the thread that acquired the reference.
+    #[inline]
+    pub fn thread(&self) -> ThreadId {
+        self.thread
+    }
+
+    /// Returns the backtrace where the reference was acquired.
+    #[inline]
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+}
+
+impl std::fmt::Display for TrackedRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} of {:#} at {:#x} on {:?}",
+            self.kind, self.iid, self.object, self.thread
+        )
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static REFS: Mutex<BTreeMap<usize, Vec<TrackedRef>>> = Mutex::new(BTreeMap::new());
+static LEAK_HOOK: Mutex<fn(&LeakReport)> = Mutex::new(print_leaks);
+
+fn refs() -> std::sync::MutexGuard<'static, BTreeMap<usize, Vec<TrackedRef>>> {
+    REFS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn is_owned(kind: RefKind) -> bool {
+    kind != RefKind::AddRef
+}
+
+pub(crate) fn acquire<T: Interface>(p: &ComPtr<T>, kind: RefKind) {
+    let r = TrackedRef {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        object: p.as_ptr() as usize,
+        iid: Guid::from_guid(&T::uuidof()),
+        kind,
+        thread: std::thread::current().id(),
+        backtrace: Arc::new(Backtrace::force_capture()),
+    };
+    refs().entry(r.object).or_default().push(r);
+}
+
+/// Removes the latest record of `p` whose kind is `AddRef` if `add_ref` is `true`, otherwise
+/// `New` or `Clone`. The records of other IIDs are removed only when the IID has no records.
+pub(crate) fn release<T: Interface>(p: &ComPtr<T>, add_ref: bool) {
+    let object = p.as_ptr() as usize;
+    let iid = Guid::from_guid(&T::uuidof());
+    let mut refs = refs();
+    if let Some(v) = refs.get_mut(&object) {
+        let matches = |r: &TrackedRef| is_owned(r.kind) != add_ref;
+        let index = v
+            .iter()
+            .rposition(|r| matches(r) && r.iid == iid)
+            .or_else(|| v.iter().rposition(matches));
+        if let Some(index) = index {
+            v.remove(index);
+        }
+        if v.is_empty() {
+            refs.remove(&object);
+        }
+    }
+}
+
+/// Changes the IID of the latest `New` or `Clone` record of `p` to `U`.
+pub(crate) fn upcast<T: Interface, U: Interface>(p: &ComPtr<T>) {
+    let iid = Guid::from_guid(&T::uuidof());
+    if let Some(r) = refs()
+        .get_mut(&(p.as_ptr() as usize))
+        .and_then(|v| v.iter_mut().rev().find(|r| is_owned(r.kind) && r.iid == iid))
+    {
+        r.iid = Guid::from_guid(&U::uuidof());
+    }
+}
+
+/// Returns a snapshot of the references that are not released in the order of the records.
+pub fn live() -> Vec<TrackedRef> {
+    let mut v = refs().values().flatten().cloned().collect::<Vec<_>>();
+    v.sort_by_key(|r| r.id);
+    v
+}
+
+/// Sets the hook that receives the leaks when a [`ComApartment`](crate::ComApartment) that
+/// initialized the COM library is dropped.
+///
+/// The leaks are the references that were acquired on the thread while the apartment was alive
+/// and not released. The default hook is [`print_leaks`].
+pub fn set_leak_hook(hook: fn(&LeakReport)) {
+    *LEAK_HOOK.lock().unwrap_or_else(|e| e.into_inner()) = hook;
+}
+
+/// Prints the leaks with the backtraces to stderr.
+///
+/// This is the default hook of [`set_leak_hook`].
+pub fn print_leaks(leaks: &LeakReport) {
+    eprintln!("{:#}", leaks);
+}
+
+pub(crate) fn report_leaks(leaks: &LeakReport) {
+    let hook = *LEAK_HOOK.lock().unwrap_or_else(|e| e.into_inner());
+    hook(leaks);
+}
+
+/// A point of the records on the current thread.
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    id: u64,
+    thread: ThreadId,
+}
+
+impl Checkpoint {
+    /// Creates a checkpoint of the current thread.
+    #[inline]
+    pub fn new() -> Checkpoint {
+        Checkpoint {
+            id: NEXT_ID.load(Ordering::Relaxed),
+            thread: std::thread::current().id(),
+        }
+    }
+
+    /// Returns the references that were acquired on the thread after the checkpoint
+    /// and are not released.
+    pub fn leaks(&self) -> LeakReport {
+        let mut refs = live();
+        refs.retain(|r| r.id >= self.id && r.thread == self.thread);
+        LeakReport { refs }
+    }
+
+    /// Panics with the report if there are leaks such as at the end of a test.
+    pub fn assert_no_leaks(&self) {
+        let leaks = self.leaks();
+        if !leaks.is_empty() {
+            panic!("{}", leaks);
+        }
+    }
+}
+
+impl Default for Checkpoint {
+    #[inline]
+    fn default() -> Self {
+        Checkpoint::new()
+    }
+}
+
+/// The references that are not released.
+///
+/// `{}` displays the references, and `{:#}` also displays the backtraces.
+#[derive(Clone, Debug)]
+pub struct LeakReport {
+    refs: Vec<TrackedRef>,
+}
+
+impl LeakReport {
+    /// Returns the references.
+    #[inline]
+    pub fn refs(&self) -> &[TrackedRef] {
+        &self.refs
+    }
+
+    /// Returns the number of the references.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.refs.len()
+    }
+
+    /// Returns `true` when there are no leaks.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.refs.is_empty()
+    }
+}
+
+impl std::fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} references were not released", self.refs.len())?;
+        for r in &self.refs {
+            write!(f, "\n  {}", r)?;
+            if f.alternate() {
+                write!(f, "\n{}", r.backtrace)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{IValue, Value};
+    use crate::{com_class, ComApartment};
+
+    #[com_class(IUnknown)]
+    struct Object;
+
+    #[test]
+    fn track_test() {
+        let checkpoint = Checkpoint::new();
+        let p = Object.into_com_ptr();
+        let q = p.clone();
+        p.add_ref();
+        let leaks = checkpoint.leaks();
+        assert_eq!(
+            leaks.refs().iter().map(|r| r.kind()).collect::<Vec<_>>(),
+            vec![RefKind::New, RefKind::Clone, RefKind::AddRef]
+        );
+        assert!(leaks.refs().iter().all(|r| r.object() == p.as_ptr() as *mut c_void));
+        assert_eq!(leaks.refs()[0].iid(), Guid::from_guid(&IUnknown::uuidof()));
+        assert!(live().iter().any(|r| r.object() == p.as_ptr() as *mut c_void));
+        unsafe { p.release() };
+        drop(q);
+        assert_eq!(checkpoint.leaks().len(), 1);
+        drop(p);
+        checkpoint.assert_no_leaks();
+    }
+
+    #[test]
+    fn leak_test() {
+        let checkpoint = Checkpoint::new();
+        let p = Object.into_com_ptr();
+        p.add_ref();
+        drop(p.clone());
+        let leaks = checkpoint.leaks();
+        assert_eq!(leaks.len(), 2);
+        assert!(leaks.to_string().starts_with("2 references were not released"));
+        assert!(std::panic::catch_unwind(|| checkpoint.assert_no_leaks()).is_err());
+        unsafe { p.release() };
+        let raw = p.into_raw();
+        checkpoint.assert_no_leaks();
+        drop(unsafe { ComPtr::from_raw(raw) });
+        checkpoint.assert_no_leaks();
+    }
+
+    #[test]
+    fn iid_test() {
+        let checkpoint = Checkpoint::new();
+        let value = Value::new(1).into_com_ptr();
+        assert_eq!(unsafe { value.Get() }, 1);
+        let unknown: ComPtr<IUnknown> = value.clone().upcast();
+        let iids = || {
+            let mut v = checkpoint.leaks().refs().iter().map(|r| r.iid()).collect::<Vec<_>>();
+            v.sort_by_key(|iid| iid.to_string());
+            v
+        };
+        let mut expected = vec![
+            Guid::from_guid(&IValue::uuidof()),
+            Guid::from_guid(&IUnknown::uuidof()),
+        ];
+        expected.sort_by_key(|iid| iid.to_string());
+        assert_eq!(iids(), expected);
+        unknown.add_ref();
+        drop(value);
+        let leaks = checkpoint.leaks();
+        assert_eq!(
+            leaks.refs().iter().map(|r| (r.iid(), r.kind())).collect::<Vec<_>>(),
+            vec![
+                (Guid::from_guid(&IUnknown::uuidof()), RefKind::Clone),
+                (Guid::from_guid(&IUnknown::uuidof()), RefKind::AddRef),
+            ]
+        );
+        unsafe { unknown.release() };
+        assert_eq!(checkpoint.leaks().refs()[0].kind(), RefKind::Clone);
+        drop(unknown);
+        checkpoint.assert_no_leaks();
+    }
+
+    static HOOKED: Mutex<Vec<(ThreadId, usize)>> = Mutex::new(Vec::new());
+
+    fn hook(leaks: &LeakReport) {
+        let thread = leaks.refs()[0].thread();
+        HOOKED.lock().unwrap().push((thread, leaks.len()));
+    }
+
+    #[test]
+    fn hook_test() {
+        set_leak_hook(hook);
+        let thread = std::thread::spawn(|| {
+            let apartment = ComApartment::mta().unwrap();
+            let p = Object.into_com_ptr();
+            p.add_ref();
+            drop(apartment);
+            unsafe { p.release() };
+            std::thread::current().id()
+        })
+        .join()
+        .unwrap();
+        set_leak_hook(print_leaks);
+        assert!(HOOKED.lock().unwrap().contains(&(thread, 2)));
+    }
+
+    #[test]
+    fn thread_test() {
+        let checkpoint = Checkpoint::new();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let th = std::thread::spawn(move || {
+            let p = Object.into_com_ptr();
+            tx.send(p.as_ptr() as usize).unwrap();
+            done_rx.recv().unwrap();
+        });
+        let object = rx.recv().unwrap();
+        assert!(live().iter().any(|r| r.object() as usize == object));
+        checkpoint.assert_no_leaks();
+        done_tx.send(()).unwrap();
+        th.join().unwrap();
+    }
+}